@@ -6,6 +6,12 @@
 #include "method-utils.hpp"
 #include "field-utils.hpp"
 #include "unity-utils.hpp"
+#include "iterator-utils.hpp"
+#include "nullable-utils.hpp"
+#include "enum-utils.hpp"
+#include "reflection-utils.hpp"
+#include "hook-utils.hpp"
+#include "method-table.hpp"
 
 #include "beatsaber-hook/shared/utils/byref.hpp"
 #include "beatsaber-hook/shared/utils/il2cpp-utils-methods.hpp"
@@ -0,0 +1,77 @@
+#pragma once
+
+#include <array>
+#include <concepts>
+#include <cstddef>
+#include <optional>
+#include <string_view>
+#include <utility>
+
+#if __has_include(<format>)
+#include <format>
+#endif
+
+#if __has_include(<fmt/format.h>)
+#include <fmt/format.h>
+#endif
+
+namespace {
+namespace cordl_internals {
+  template <typename E, std::size_t N>
+  using EnumEntries = std::array<std::pair<std::string_view, E>, N>;
+
+  /// @brief name of the first entry with the given value, empty if there is none
+  template <typename E, std::size_t N>
+  constexpr std::string_view enum_name(EnumEntries<E, N> const& entries, E value) noexcept {
+    for (auto const& [name, entry_value] : entries) {
+      if (entry_value == value) return name;
+    }
+    return {};
+  }
+
+  /// @brief value of the entry with the given name, matched exactly
+  template <typename E, std::size_t N>
+  constexpr std::optional<E> enum_value(EnumEntries<E, N> const& entries, std::string_view name) noexcept {
+    for (auto const& [entry_name, value] : entries) {
+      if (entry_name == name) return value;
+    }
+    return std::nullopt;
+  }
+
+  /// @brief generated enum wrappers, which have a name table
+  template <typename T>
+  concept cordl_enum = requires(T const& t) {
+    T::__CORDL_ENUM_ENTRIES;
+    { t.to_string() } -> std::convertible_to<std::string_view>;
+    static_cast<typename T::__CORDL_BACKING_ENUM_TYPE>(t);
+  };
+} // namespace cordl_internals
+} // end anonymous namespace
+
+// values without a name (e.g. combined flags) are printed as numbers
+#if __has_include(<format>)
+template <::cordl_internals::cordl_enum T>
+struct std::formatter<T, char> : std::formatter<std::string_view, char> {
+  auto format(T const& value, std::format_context& ctx) const {
+    auto name = value.to_string();
+    if (name.empty()) {
+      return std::format_to(ctx.out(), "{}", static_cast<typename T::__CORDL_BACKING_ENUM_TYPE>(value));
+    }
+    return std::formatter<std::string_view, char>::format(name, ctx);
+  }
+};
+#endif
+
+#if __has_include(<fmt/format.h>)
+template <::cordl_internals::cordl_enum T>
+struct fmt::formatter<T> : fmt::formatter<std::string_view> {
+  template <typename FormatContext>
+  auto format(T const& value, FormatContext& ctx) const {
+    auto name = value.to_string();
+    if (name.empty()) {
+      return fmt::format_to(ctx.out(), "{}", static_cast<typename T::__CORDL_BACKING_ENUM_TYPE>(value));
+    }
+    return fmt::formatter<std::string_view>::format(name, ctx);
+  }
+};
+#endif
@@ -100,6 +100,75 @@ namespace cordl_internals {
     return val;
   }
 
+#pragma endregion // static field getters
+
+#pragma region static field offsets
+
+  /// @brief initializes a class and runs its static constructor, unless that already happened
+  /// @tparam klass_resolver method to get the Il2CppClass* to initialize
+  template <auto klass_resolver>
+  CORDL_HIDDEN void EnsureClassInit() {
+    [[maybe_unused]] static bool initialized = []() {
+      auto* klass = klass_resolver();
+      if (!klass)
+        throw NullException("Class to initialize is null!");
+      ::il2cpp_functions::runtime_class_init(klass);
+      return true;
+    }();
+  }
+
+  /// @brief static data of a class, only valid once the class is initialized
+  /// @tparam klass_resolver method to get the Il2CppClass* on which the fields reside
+  template <auto klass_resolver>
+  CORDL_HIDDEN std::byte* StaticFieldData() {
+    static auto* klass = klass_resolver();
+    if (!klass)
+      throw NullException("Class for static field data is null!");
+    return static_cast<std::byte*>(klass->static_fields);
+  }
+
+  /// @brief pointer to a static field at its offset in the class static data
+  /// @tparam offset offset of the field, from the metadata
+  /// @tparam name field name, only used to check the offset against the runtime
+  /// @tparam klass_resolver method to get the Il2CppClass* on which the field resides
+  template <typename T, std::size_t offset, internal::NTTPString name, auto klass_resolver>
+  CORDL_HIDDEN T* StaticFieldAtOffset() {
+#ifndef NO_RUNTIME_STATIC_FIELD_OFFSET_CHECKS
+    [[maybe_unused]] static bool checked = []() {
+      auto* field = FindField<name, klass_resolver>();
+      if (static_cast<std::size_t>(field->offset) != offset)
+        throw FieldException(std::string("Offset of static field ") + name.data.data() +
+                             " does not match its metadata offset");
+      return true;
+    }();
+#endif
+    return reinterpret_cast<T*>(StaticFieldData<klass_resolver>() + offset);
+  }
+
+  /// @brief method to get a static field at its offset
+  template <typename T, std::size_t offset, internal::NTTPString name, auto klass_resolver>
+  [[nodiscard]] CORDL_HIDDEN T getStaticFieldAtOffset() {
+    if constexpr (il2cpp_utils::il2cpp_reference_type_wrapper<T>) {
+      return T(*StaticFieldAtOffset<void*, offset, name, klass_resolver>());
+    } else {
+      return *StaticFieldAtOffset<T, offset, name, klass_resolver>();
+    }
+  }
+
+  /// @brief method to set a static field at its offset, reference types go through the write barrier
+  template <typename T, std::size_t offset, internal::NTTPString name, auto klass_resolver>
+  CORDL_HIDDEN void setStaticFieldAtOffset(T&& v) {
+    if constexpr (::il2cpp_utils::il2cpp_reference_type<T>) {
+      auto* pointer = StaticFieldAtOffset<void*, offset, name, klass_resolver>();
+      auto value = il2cpp_utils::il2cpp_reference_type_value<T>(std::forward<T>(v));
+      ::il2cpp_functions::gc_wbarrier_set_field(nullptr, pointer, value);
+    } else {
+      *StaticFieldAtOffset<T, offset, name, klass_resolver>() = std::forward<T>(v);
+    }
+  }
+
+#pragma endregion // static field offsets
+
   /// @brief method to set a field for a generic container type
   template <typename T> CORDL_HIDDEN void setInstanceField(Il2CppObject* owner, T* pointer, std::type_identity_t<T>& value) {
     // if a ref type, use wbarrier
@@ -111,6 +180,5 @@ namespace cordl_internals {
     }
   }
 
-#pragma endregion // static field getters
 }
 } // end anonymous namespace
@@ -0,0 +1,12 @@
+#pragma once
+
+namespace {
+namespace cordl_internals {
+  /// @brief hook declaration of a generated method, specialized in the `_hooks.hpp` header of its type
+  /// `hook_t` is the native function pointer type: the instance (for instance methods), the parameters and a trailing MethodInfo*
+  /// `addrs` is the address relative to the libil2cpp base, `methodInfo()` resolves the MethodInfo
+  /// @tparam method pointer to the generated method, e.g. `&GlobalNamespace::Foo::Bar`
+  template <auto method>
+  struct MethodHook;
+} // namespace cordl_internals
+} // end anonymous namespace
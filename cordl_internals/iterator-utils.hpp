@@ -0,0 +1,89 @@
+#pragma once
+
+#include <compare>
+#include <cstddef>
+#include <cstdint>
+#include <iterator>
+
+namespace {
+namespace cordl_internals {
+  // The collection interfaces are only used through these templates,
+  // so they need to be complete where a collection is iterated, not where it is declared
+
+  template <typename Collection>
+  int32_t collection_count(void* self) {
+    return static_cast<Collection*>(self)->get_Count();
+  }
+
+  template <typename Enumerable>
+  auto collection_enumerator(void* self) {
+    return static_cast<Enumerable*>(self)->GetEnumerator();
+  }
+
+  /// @brief random access iterator over a collection with an indexer, like IList<T> or IReadOnlyList<T>
+  template <typename List, typename T>
+  struct IndexIterator {
+    using iterator_category = std::random_access_iterator_tag;
+    using value_type = T;
+    using difference_type = std::ptrdiff_t;
+    using reference = T;
+
+    constexpr IndexIterator() noexcept = default;
+    constexpr IndexIterator(List* list, int32_t index) noexcept : list(list), index(index) {}
+
+    T operator*() const { return list->get_Item(index); }
+    T operator[](difference_type n) const { return list->get_Item(static_cast<int32_t>(index + n)); }
+
+    constexpr IndexIterator& operator++() noexcept { ++index; return *this; }
+    constexpr IndexIterator operator++(int) noexcept { auto copy = *this; ++index; return copy; }
+    constexpr IndexIterator& operator--() noexcept { --index; return *this; }
+    constexpr IndexIterator operator--(int) noexcept { auto copy = *this; --index; return copy; }
+
+    constexpr IndexIterator& operator+=(difference_type n) noexcept { index += static_cast<int32_t>(n); return *this; }
+    constexpr IndexIterator& operator-=(difference_type n) noexcept { index -= static_cast<int32_t>(n); return *this; }
+
+    friend constexpr IndexIterator operator+(IndexIterator it, difference_type n) noexcept { return it += n; }
+    friend constexpr IndexIterator operator+(difference_type n, IndexIterator it) noexcept { return it += n; }
+    friend constexpr IndexIterator operator-(IndexIterator it, difference_type n) noexcept { return it -= n; }
+    friend constexpr difference_type operator-(IndexIterator a, IndexIterator b) noexcept { return a.index - b.index; }
+
+    friend constexpr bool operator==(IndexIterator a, IndexIterator b) noexcept { return a.index == b.index; }
+    friend constexpr auto operator<=>(IndexIterator a, IndexIterator b) noexcept { return a.index <=> b.index; }
+
+  private:
+    List* list = nullptr;
+    int32_t index = 0;
+  };
+
+  /// @brief input iterator over an IEnumerator<T>, the default constructed iterator is the end
+  /// @tparam BaseEnumerator the non generic IEnumerator, which declares MoveNext
+  template <typename Enumerator, typename BaseEnumerator, typename T>
+  struct EnumeratorIterator {
+    using iterator_category = std::input_iterator_tag;
+    using value_type = T;
+    using difference_type = std::ptrdiff_t;
+    using reference = T;
+
+    constexpr EnumeratorIterator() noexcept = default;
+    explicit EnumeratorIterator(Enumerator* enumerator) : enumerator(enumerator) { advance(); }
+
+    T operator*() const { return enumerator->get_Current(); }
+
+    EnumeratorIterator& operator++() { advance(); return *this; }
+    void operator++(int) { advance(); }
+
+    friend constexpr bool operator==(EnumeratorIterator const& a, EnumeratorIterator const& b) noexcept {
+      return a.enumerator == b.enumerator;
+    }
+
+  private:
+    void advance() {
+      if (enumerator && !reinterpret_cast<BaseEnumerator*>(enumerator)->MoveNext()) {
+        enumerator = nullptr;
+      }
+    }
+
+    Enumerator* enumerator = nullptr;
+  };
+} // namespace cordl_internals
+} // end anonymous namespace
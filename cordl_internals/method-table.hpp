@@ -0,0 +1,39 @@
+#pragma once
+
+// only generated when cordl runs with --method-table
+#if __has_include("method-table-size.hpp")
+#include "method-table-size.hpp"
+
+#include "beatsaber-hook/shared/utils/il2cpp-functions.hpp"
+#include "beatsaber-hook/shared/utils/utils.h"
+#include "method-utils.hpp"
+
+#include <atomic>
+#include <cstdint>
+
+// outside of the anonymous namespace, so all translation units share one table
+namespace cordl_method_table {
+  /// @brief MethodInfo of every method, indexed by metadata method index
+  inline std::atomic<MethodInfo const*> entries[CORDL_METHOD_TABLE_SIZE]{};
+
+  /// @brief looks up a method table entry, resolving it by token on first use
+  inline MethodInfo const* get(uint32_t index, Il2CppClass* (*klass)(), uint32_t rid) {
+    // acquire pairs with the release below, so the MethodInfo is fully visible to other threads
+    auto* method = entries[index].load(std::memory_order_acquire);
+    if (method) [[likely]] return method;
+
+    // racing resolves find the same MethodInfo
+    method = THROW_UNLESS(::cordl_internals::FindMethodByToken(klass(), rid));
+    entries[index].store(method, std::memory_order_release);
+    return method;
+  }
+} // namespace cordl_method_table
+
+namespace {
+namespace cordl_internals {
+  CORDL_HIDDEN inline MethodInfo const* method_table_get(uint32_t index, Il2CppClass* (*klass)(), uint32_t rid) {
+    return ::cordl_method_table::get(index, klass, rid);
+  }
+} // namespace cordl_internals
+} // end anonymous namespace
+#endif
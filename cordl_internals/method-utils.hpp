@@ -68,5 +68,38 @@ CORDL_HIDDEN TOut RunMethodRethrow(T&& instance, MethodInfo const* method, TArgs
 
   return ::il2cpp_utils::RunMethodRethrow<TOut, checkTypes>(std::forward<T>(instance), method, std::forward<TArgs>(params)...);
 }
+
+/// @brief finds a method of klass by the row of its metadata token, unlike FindMethod this tells apart overloads that only differ by ref-ness
+CORDL_HIDDEN inline MethodInfo const* FindMethodByToken(Il2CppClass* klass, uint32_t rid) {
+  ::il2cpp_functions::Init();
+
+  void* iter = nullptr;
+  while (auto method = ::il2cpp_functions::class_get_methods(klass, &iter)) {
+    if ((method->token & 0x00FFFFFF) == rid) return method;
+  }
+  return nullptr;
+}
+
+/// @brief initializes the class declaring method and runs its static constructor, which direct calls skip
+CORDL_HIDDEN inline void EnsureMethodClassInit(MethodInfo const* method) {
+  ::il2cpp_functions::runtime_class_init(method->klass);
+}
+
+/// @brief calls the native function at rva in libil2cpp, params are the instance (if any) and the parameters, then the MethodInfo
+/// there are no null checks and il2cpp exceptions are not rethrown
+/// the params are passed as is, so they have to share the ABI of the raw il2cpp types
+template <uintptr_t rva, typename TOut, typename... TArgs>
+CORDL_HIDDEN TOut RunMethodDirect(TArgs... params) {
+  static_assert((std::is_trivially_copyable_v<TArgs> && ...), "Direct calls need trivially copyable params");
+  static_assert(((!::il2cpp_utils::il2cpp_reference_type<TArgs> || sizeof(TArgs) == sizeof(void*)) && ...),
+                "Reference type params of direct calls have to be pointer sized");
+  static_assert(!::il2cpp_utils::il2cpp_reference_type<TOut> || sizeof(TOut) == sizeof(void*),
+                "Reference type returns of direct calls have to be pointer sized");
+
+  using function_t = TOut (*)(TArgs...);
+  static auto function = reinterpret_cast<function_t>(getRealOffset(reinterpret_cast<void*>(rva)));
+
+  return function(params...);
+}
 } // namespace cordl_internals
 } // end anonymous namespace
@@ -0,0 +1,71 @@
+#pragma once
+
+#include "concepts.hpp"
+#include "exceptions.hpp"
+
+#include <cstdlib>
+#include <optional>
+#include <string>
+#include <typeinfo>
+
+#if __has_feature(cxx_exceptions)
+#define __NULLABLEW_NO_VALUE_CHECK(...)                                        \
+  if (has_value())                                                             \
+    return __VA_ARGS__;                                                        \
+  throw ::cordl_internals::NullException(std::string(typeid(*this).name()) +   \
+                                         " has no value")
+
+#else
+#define __NULLABLEW_NO_VALUE_CHECK(...)                                        \
+  if (has_value())                                                             \
+    return __VA_ARGS__;                                                        \
+  std::abort()
+#endif
+
+/// @brief System.Nullable<T> with an std::optional like interface.
+/// The layout matches the managed struct, a bool followed by the value
+template <typename T> struct NullableW {
+  constexpr NullableW() noexcept = default;
+  constexpr NullableW(T value) noexcept : hasValue(true), innerValue(value) {}
+  constexpr NullableW(std::nullopt_t) noexcept {}
+  constexpr NullableW(std::optional<T> const &opt) noexcept
+      : hasValue(opt.has_value()), innerValue(opt.value_or(T{})) {}
+
+  [[nodiscard]] constexpr bool has_value() const noexcept { return hasValue; }
+
+  constexpr explicit operator bool() const noexcept { return has_value(); }
+
+  constexpr T &value() { __NULLABLEW_NO_VALUE_CHECK(innerValue); }
+
+  constexpr T const &value() const { __NULLABLEW_NO_VALUE_CHECK(innerValue); }
+
+  constexpr T value_or(T fallback) const noexcept {
+    return has_value() ? innerValue : fallback;
+  }
+
+  constexpr T *operator->() { return &value(); }
+
+  constexpr T const *operator->() const { return &value(); }
+
+  constexpr T &operator*() { return value(); }
+
+  constexpr T const &operator*() const { return value(); }
+
+  constexpr operator std::optional<T>() const noexcept {
+    if (!has_value())
+      return std::nullopt;
+    return innerValue;
+  }
+
+  constexpr bool operator==(std::nullopt_t) const noexcept { return !has_value(); }
+
+private:
+  bool hasValue = false;
+  T innerValue{};
+};
+
+// the runtime specific parts, marking it as a value type and resolving its class,
+// are written by cordl from the runtime profile
+#if __has_include("nullable-runtime.hpp")
+#include "nullable-runtime.hpp"
+#endif
@@ -0,0 +1,52 @@
+#pragma once
+
+#include <cstddef>
+#include <cstdint>
+#include <string_view>
+#include <tuple>
+#include <type_traits>
+
+namespace {
+namespace cordl_internals {
+  /// @brief a value type instance field, offsets are relative to the start of the struct
+  template <typename T>
+  struct ValueTypeField {
+    using type = T;
+
+    std::string_view name;
+    std::size_t offset;
+    std::size_t size;
+  };
+
+  /// @brief field descriptions of a value type, specialized for every non generic value type
+  /// `fields` is a tuple of ValueTypeField
+  template <typename T>
+  struct value_type_fields;
+
+  template <typename T>
+  requires(requires { T::__CORDL_FIELDS; })
+  struct value_type_fields<T> {
+    static constexpr auto const& fields = T::__CORDL_FIELDS;
+  };
+
+  template <typename T>
+  concept has_value_type_fields = requires { value_type_fields<std::remove_cv_t<T>>::fields; };
+
+  /// @brief the field of instance described by field
+  template <typename T, typename F>
+  auto& field_ref(T& instance, ValueTypeField<F> const& field) noexcept {
+    using bytes_t = std::conditional_t<std::is_const_v<T>, uint8_t const, uint8_t>;
+    using field_t = std::conditional_t<std::is_const_v<T>, F const, F>;
+
+    return *reinterpret_cast<field_t*>(reinterpret_cast<bytes_t*>(&instance) + field.offset);
+  }
+
+  /// @brief calls fn(field, value) for every field of instance
+  template <has_value_type_fields T, typename Fn>
+  void for_each_field(T& instance, Fn&& fn) {
+    std::apply(
+        [&](auto const&... fields) { (fn(fields, field_ref(instance, fields)), ...); },
+        value_type_fields<std::remove_cv_t<T>>::fields);
+  }
+} // namespace cordl_internals
+} // end anonymous namespace
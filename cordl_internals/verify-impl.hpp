@@ -0,0 +1,57 @@
+#pragma once
+
+// included at the end of the generated cordl_verify.cpp, after its `types` and `fields` tables
+
+#include "verify.hpp"
+#include "beatsaber-hook/shared/utils/il2cpp-functions.hpp"
+
+#include <algorithm>
+#include <span>
+#include <string>
+
+std::size_t cordl_verify::verify_layouts(std::size_t max_logged, std::function<void(std::string_view)> const& log) {
+  std::size_t mismatches = 0;
+  auto report = [&](TypeLayout const& type, std::string const& message) {
+    if (mismatches++ < max_logged) {
+      log(std::string(type.name) + ": " + message);
+    }
+  };
+
+  for (auto const& type : types) {
+    auto* klass = ::il2cpp_functions::MetadataCache_GetTypeInfoFromTypeDefinitionIndex(type.type_definition_index);
+    if (!klass) {
+      report(type, "no class for type definition index " + std::to_string(type.type_definition_index));
+      continue;
+    }
+    // sets up the field offsets and instance size, without running the static constructor
+    ::il2cpp_functions::Class_Init(klass);
+
+    // at runtime value types are laid out after an object header, the generated structs don't have one
+    std::uint32_t header = type.value_type ? sizeof(Il2CppObject) : 0;
+
+    if (type.has_instance_size && klass->instance_size != type.instance_size + header) {
+      report(type, "instance size " + std::to_string(klass->instance_size) + ", generated " +
+                       std::to_string(type.instance_size + header));
+    }
+
+    if (klass->method_count != type.method_count) {
+      report(type, "method count " + std::to_string(klass->method_count) + ", generated " +
+                       std::to_string(type.method_count));
+    }
+
+    for (auto const& field : std::span(fields).subspan(type.fields_start, type.fields_count)) {
+      auto runtime_fields = std::span(klass->fields, klass->field_count);
+      auto it = std::find_if(runtime_fields.begin(), runtime_fields.end(),
+                             [&](FieldInfo const& info) { return info.name && field.name == info.name; });
+
+      if (it == runtime_fields.end()) {
+        report(type, "no field " + std::string(field.name));
+      } else if (static_cast<std::uint32_t>(it->offset) != field.offset + header) {
+        report(type, "field " + std::string(field.name) + " at offset " + std::to_string(it->offset) + ", generated " +
+                         std::to_string(field.offset + header));
+      }
+    }
+  }
+
+  return mismatches;
+}
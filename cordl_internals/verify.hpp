@@ -0,0 +1,34 @@
+#pragma once
+
+#include <array>
+#include <cstddef>
+#include <cstdint>
+#include <functional>
+#include <string_view>
+
+// only usable when cordl_verify.cpp was generated and is compiled into the mod
+namespace cordl_verify {
+  /// @brief generated offset of an instance field, value type offsets exclude the object header
+  struct FieldLayout {
+    std::string_view name;
+    std::uint32_t offset;
+  };
+
+  /// @brief generated layout of a type, its fields are a range of the field table
+  struct TypeLayout {
+    std::string_view name;
+    std::int32_t type_definition_index;
+    bool value_type;
+    bool has_instance_size;
+    std::uint32_t instance_size;
+    std::uint16_t method_count;
+    std::size_t fields_start;
+    std::size_t fields_count;
+  };
+
+  /// @brief compares the generated layout of every type with its Il2CppClass, call once the runtime is loaded
+  /// @param max_logged how many mismatches are passed to log, the rest are only counted
+  /// @param log called with a description of each logged mismatch
+  /// @return the number of mismatches, 0 if the generated headers match the running game
+  std::size_t verify_layouts(std::size_t max_logged, std::function<void(std::string_view)> const& log);
+} // namespace cordl_verify
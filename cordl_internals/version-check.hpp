@@ -0,0 +1,68 @@
+#pragma once
+
+#include <link.h>
+
+#include <cstdint>
+#include <cstring>
+#include <optional>
+#include <string>
+#include <string_view>
+
+namespace cordl_version {
+  /// @brief GNU build-id of a loaded library as lowercase hex, read from its PT_NOTE segments
+  /// @param library end of the path of the library, e.g. "libil2cpp.so"
+  inline std::optional<std::string> loaded_build_id(std::string_view library) {
+    struct Search {
+      std::string_view library;
+      std::optional<std::string> build_id;
+    } search{ library, std::nullopt };
+
+    dl_iterate_phdr(
+        [](dl_phdr_info* info, size_t, void* data) -> int {
+          auto& search = *static_cast<Search*>(data);
+          if (!info->dlpi_name || !std::string_view(info->dlpi_name).ends_with(search.library)) return 0;
+
+          for (int i = 0; i < info->dlpi_phnum; i++) {
+            auto const& phdr = info->dlpi_phdr[i];
+            if (phdr.p_type != PT_NOTE) continue;
+
+            auto const* note = reinterpret_cast<std::uint8_t const*>(info->dlpi_addr + phdr.p_vaddr);
+            auto const* end = note + phdr.p_memsz;
+            while (note + sizeof(ElfW(Nhdr)) <= end) {
+              auto const* header = reinterpret_cast<ElfW(Nhdr) const*>(note);
+              auto const* name = note + sizeof(ElfW(Nhdr));
+              auto const* desc = name + ((header->n_namesz + 3) & ~3u);
+
+              if (header->n_type == NT_GNU_BUILD_ID && header->n_namesz == 4 && std::memcmp(name, "GNU", 4) == 0) {
+                constexpr char digits[] = "0123456789abcdef";
+                std::string hex;
+                for (std::uint32_t j = 0; j < header->n_descsz; j++) {
+                  hex += digits[desc[j] >> 4];
+                  hex += digits[desc[j] & 0xf];
+                }
+                search.build_id = std::move(hex);
+                return 1;
+              }
+
+              note = desc + ((header->n_descsz + 3) & ~3u);
+            }
+          }
+          // found the library, but it has no build-id
+          return 1;
+        },
+        &search);
+
+    return search.build_id;
+  }
+
+  /// @brief whether a loaded library has the expected build-id
+  /// @return nullopt if the expected build-id is empty, or the library has none
+  inline std::optional<bool> matches_loaded_build_id(std::string_view expected, std::string_view library) {
+    if (expected.empty()) return std::nullopt;
+
+    auto loaded = loaded_build_id(library);
+    if (!loaded) return std::nullopt;
+
+    return *loaded == expected;
+  }
+} // namespace cordl_version
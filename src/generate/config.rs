@@ -1,4 +1,34 @@
-use std::path::PathBuf;
+use std::{path::PathBuf, str::FromStr};
+
+use super::{fingerprint::InputFingerprint, runtime_profile::RuntimeProfile};
+
+/// How generated method bodies find the method they call
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MethodLookup {
+    /// `FindMethod` by name and parameter types
+    #[default]
+    Name,
+    /// by metadata token, which tells overloads apart even if they only differ by ref-ness
+    Token,
+    /// by token, and non virtual methods call their native function pointer directly,
+    /// relative to the libil2cpp base. This skips the null checks and exception rethrowing of `RunMethodRethrow`
+    Address,
+}
+
+impl FromStr for MethodLookup {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(Self::Name),
+            "token" => Ok(Self::Token),
+            "address" => Ok(Self::Address),
+            _ => Err(format!(
+                "Unknown method lookup {s}, expected one of name, token, address"
+            )),
+        }
+    }
+}
 
 pub struct GenerationConfig {
     pub source_path: PathBuf,
@@ -6,6 +36,18 @@ pub struct GenerationConfig {
     pub dst_internals_path: PathBuf,
     pub dst_header_internals_file: PathBuf,
     pub use_anonymous_namespace: bool,
+    pub runtime: RuntimeProfile,
+    pub method_lookup: MethodLookup,
+    /// Whether to write a `_hooks.hpp` header with hook declarations next to each type
+    pub hook_headers: bool,
+    /// Whether non generic methods resolve their `MethodInfo` through one shared table
+    /// instead of a guarded static per method
+    pub method_table: bool,
+    /// Whether static fields are read from the class static data at their metadata offset
+    /// instead of through a `FieldInfo` found by name
+    pub static_field_offsets: bool,
+    /// The inputs the headers are generated from, stamped into `cordl_version.hpp`
+    pub fingerprint: InputFingerprint,
 }
 
 impl GenerationConfig {
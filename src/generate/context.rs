@@ -11,17 +11,18 @@ use brocolib::global_metadata::TypeDefinitionIndex;
 use color_eyre::eyre::ContextCompat;
 
 use itertools::Itertools;
-use log::{info, trace};
+use log::{info, trace, warn};
 use pathdiff::diff_paths;
 
 use crate::generate::cs_type::CORDL_NO_INCLUDE_IMPL_DEFINE;
-use crate::generate::members::CppForwardDeclare;
+use crate::generate::members::{CppForwardDeclare, CppNonMember};
 use crate::generate::{members::CppInclude, type_extensions::TypeDefinitionExtensions};
 use crate::helpers::sorting::DependencyGraph;
-use crate::STATIC_CONFIG;
+use crate::helpers::unwind::catch_panic;
 
 use super::cpp_type_tag::CppTypeTag;
 use super::cs_type::IL2CPP_OBJECT_TYPE;
+use super::failures::{TypeFailure, TypeFailureStage};
 use super::{
     config::GenerationConfig,
     cpp_type::CppType,
@@ -35,12 +36,21 @@ use super::{
 // Will hold various metadata, such as includes, type definitions, and extraneous writes
 #[derive(Debug, Clone)]
 pub struct CppContext {
+    // root of the generated headers, includes are relative to it
+    pub header_path: PathBuf,
+
     pub typedef_path: PathBuf,
     pub type_impl_path: PathBuf,
 
     // combined header
     pub fundamental_path: PathBuf,
 
+    // hook declarations, only written if enabled
+    pub hooks_path: PathBuf,
+
+    // method bodies moved out of the headers, only written if there are any
+    pub source_file_path: PathBuf,
+
     // Types to write, typedef
     pub typedef_types: HashMap<CppTypeTag, CppType>,
 
@@ -90,6 +100,7 @@ impl CppContext {
         tdi: TypeDefinitionIndex,
         tag: CppTypeTag,
         generic_inst: Option<&Vec<usize>>,
+        failures: &mut Vec<TypeFailure>,
     ) -> CppContext {
         let t = &metadata.metadata.global_metadata.type_definitions[tdi];
 
@@ -118,6 +129,7 @@ impl CppContext {
         };
 
         let mut x = CppContext {
+            header_path: config.header_path.clone(),
             typedef_path: config
                 .header_path
                 .join(format!("{path}zzzz__{path_name}_def.hpp")),
@@ -125,14 +137,19 @@ impl CppContext {
                 .header_path
                 .join(format!("{path}zzzz__{path_name}_impl.hpp")),
             fundamental_path: config.header_path.join(format!("{path}{path_name}.hpp")),
+            hooks_path: config
+                .header_path
+                .join(format!("{path}{path_name}_hooks.hpp")),
+            source_file_path: config.source_path.join(format!("{path}{path_name}.cpp")),
             typedef_types: Default::default(),
             typealias_types: Default::default(),
         };
 
-        if metadata.blacklisted_types.contains(&tdi) {
+        // blacklisted reference types become Il2CppObject
+        let alias_to_object = |x: &mut CppContext| {
             if !t.is_value_type() {
                 x.typealias_types.insert((
-                    cpp_namespace,
+                    cpp_namespace.clone(),
                     CppUsingAlias {
                         alias: cpp_name.to_string(),
                         result: IL2CPP_OBJECT_TYPE.to_string(),
@@ -140,19 +157,35 @@ impl CppContext {
                     },
                 ));
             }
+        };
+
+        if metadata.blacklisted_types.contains(&tdi) {
+            alias_to_object(&mut x);
             return x;
         }
 
-        match CppType::make_cpp_type(metadata, config, tdi, tag, generic_inst) {
-            Some(cpptype) => {
+        match catch_panic(|| CppType::make_cpp_type(metadata, config, tdi, tag, generic_inst)) {
+            Ok(Some(cpptype)) => {
                 x.insert_cpp_type(cpptype);
             }
-            None => {
+            Ok(None) => {
                 info!(
                     "Unable to create valid CppContext for type: {}!",
                     t.full_name(metadata.metadata, true)
                 );
             }
+            Err(e) => {
+                let full_name = t.full_name(metadata.metadata, true);
+                warn!("Failed to make type {full_name}, treating it as blacklisted: {e}");
+
+                failures.push(TypeFailure {
+                    tag,
+                    full_name,
+                    stage: TypeFailureStage::Make,
+                    reason: e.to_string(),
+                });
+                alias_to_object(&mut x);
+            }
         }
 
         x
@@ -226,14 +259,13 @@ impl CppContext {
         // this is so confusing but basically gets the relative folder
         // navigation for `_config.hpp`
         let dest_path = diff_paths(
-            &STATIC_CONFIG.dst_header_internals_file,
+            &config.dst_header_internals_file,
             self.typedef_path.parent().unwrap(),
         )
         .unwrap();
 
-        // write typedefs.h include first - this makes include order mostly happy (probably System.Object would still be weird!)
-        CppInclude::new_exact("beatsaber-hook/shared/utils/typedefs.h")
-            .write(&mut typedef_writer)?;
+        // write the runtime typedefs include first - this makes include order mostly happy (probably System.Object would still be weird!)
+        CppInclude::new_exact(&config.runtime.includes.typedefs).write(&mut typedef_writer)?;
         CppInclude::new_exact(dest_path).write(&mut typedef_writer)?;
 
         // after including cordl internals
@@ -367,7 +399,7 @@ impl CppContext {
         )?;
 
         // anonymous namespace
-        if STATIC_CONFIG.use_anonymous_namespace {
+        if config.use_anonymous_namespace {
             writeln!(typedef_writer, "CORDL_MODULE_EXPORT_STRUCT namespace {{")?;
             writeln!(typeimpl_writer, "CORDL_MODULE_EXPORT_STRUCT namespace {{")?;
         }
@@ -468,7 +500,7 @@ impl CppContext {
         }
 
         // end anonymous namespace
-        if STATIC_CONFIG.use_anonymous_namespace {
+        if config.use_anonymous_namespace {
             writeln!(typedef_writer, "}} // end anonymous namespace")?;
             writeln!(typeimpl_writer, "}} // end anonymous namespace")?;
         }
@@ -492,10 +524,77 @@ impl CppContext {
             writeln!(fundamental_writer, "// IWYU pragma: end_exports")?;
         }
 
+        if config.hook_headers {
+            self.write_hooks(&typedef_types, &fundamental_include_path)?;
+        }
+
+        if self.typedef_types.values().any(|t| t.has_source()) {
+            self.write_source(&fundamental_include_path)?;
+        }
+
         // TODO: Write type impl and fundamental files here
         Ok(())
     }
 
+    /// Writes the method bodies that were moved out of the headers
+    fn write_source(&self, fundamental_include_path: &Path) -> color_eyre::Result<()> {
+        create_dir_all(
+            self.source_file_path
+                .parent()
+                .context("Failed to create all directories!")?,
+        )?;
+
+        trace!("Writing {:?}", self.source_file_path.as_path());
+        let mut source_writer = CppWriter {
+            stream: File::create(self.source_file_path.as_path())?,
+            indent: 0,
+            newline: true,
+        };
+
+        CppInclude::new_exact(fundamental_include_path).write(&mut source_writer)?;
+
+        self.typedef_types
+            .values()
+            .sorted_by(|a, b| a.cpp_name_components.cmp(&b.cpp_name_components))
+            .try_for_each(|t| t.write_source(&mut source_writer))
+    }
+
+    /// Writes the hook declarations for every method with a known address
+    fn write_hooks(
+        &self,
+        typedef_types: &[&CppType],
+        fundamental_include_path: &Path,
+    ) -> color_eyre::Result<()> {
+        let size_structs = typedef_types
+            .iter()
+            .flat_map(|t| &t.nonmember_implementations)
+            .filter_map(|n| match n.as_ref() {
+                CppNonMember::SizeStruct(s) => Some(s),
+                _ => None,
+            })
+            // abstract and extern methods have no body to hook
+            .filter(|s| s.method_data.addrs != 0)
+            .collect_vec();
+
+        if size_structs.is_empty() {
+            return Ok(());
+        }
+
+        trace!("Writing {:?}", self.hooks_path.as_path());
+        let mut hooks_writer = CppWriter {
+            stream: File::create(self.hooks_path.as_path())?,
+            indent: 0,
+            newline: true,
+        };
+
+        writeln!(hooks_writer, "#pragma once")?;
+        CppInclude::new_exact(fundamental_include_path).write(&mut hooks_writer)?;
+
+        size_structs
+            .into_iter()
+            .try_for_each(|s| s.write_hook(&mut hooks_writer))
+    }
+
     fn write_il2cpp_arg_macros(
         ty: &CppType,
         writer: &mut super::writer::CppWriter,
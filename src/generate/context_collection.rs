@@ -14,14 +14,18 @@ use log::{info, trace, warn};
 use pathdiff::diff_paths;
 
 use crate::{
-    generate::{cpp_type::CppType, cs_type::CSType},
-    STATIC_CONFIG,
+    generate::{
+        cpp_type::CppType, cs_context_collection::CsContextCollection, cs_type::CSType,
+    },
+    helpers::unwind::catch_panic,
 };
 
 use super::{
     config::GenerationConfig,
     context::CppContext,
     cpp_type_tag::{CppTypeTag, GenericInstantiation},
+    failures::{TypeFailure, TypeFailureStage},
+    fingerprint::VERSION_HEADER,
     metadata::Metadata,
     type_extensions::TypeDefinitionExtensions,
 };
@@ -34,6 +38,7 @@ pub struct CppContextCollection {
     filled_types: HashSet<CppTypeTag>,
     filling_types: HashSet<CppTypeTag>,
     borrowing_types: HashSet<CppTypeTag>,
+    failures: Vec<TypeFailure>,
 }
 
 impl CppContextCollection {
@@ -55,7 +60,21 @@ impl CppContextCollection {
         // Move ownership to local
         self.filling_types.insert(tag);
 
-        cpp_type.fill_from_il2cpp(metadata, config, self);
+        // A type that fails to fill is downgraded to an opaque type
+        // instead of aborting the whole run
+        if let Err(e) = catch_panic(|| cpp_type.fill_from_il2cpp(metadata, config, self)) {
+            let full_name = cpp_type.cs_name_components.combine_all();
+            warn!("Failed to fill type {full_name}, making it opaque: {e}");
+
+            let reason = e.to_string();
+            cpp_type.make_opaque_stub(&format!("failed to generate: {reason}"));
+            self.failures.push(TypeFailure {
+                tag,
+                full_name,
+                stage: TypeFailureStage::Fill,
+                reason,
+            });
+        }
 
         self.filled_types.insert(tag);
         self.filling_types.remove(&tag.clone());
@@ -242,7 +261,14 @@ impl CppContextCollection {
             true => {
                 // If a nested type inherits its declaring type, move it to its own CppContext
 
-                let context = CppContext::make(metadata, config, tdi, ty_data, generic_inst);
+                let context = CppContext::make(
+                    metadata,
+                    config,
+                    tdi,
+                    ty_data,
+                    generic_inst,
+                    &mut self.failures,
+                );
 
                 // Unnest type does not alias to another context or type
                 self.alias_context.remove(&ty_data);
@@ -252,9 +278,30 @@ impl CppContextCollection {
                 self.all_contexts.get_mut(&ty_data)
             }
             false => {
-                let new_cpp_type =
+                let new_cpp_type = match catch_panic(|| {
                     CppType::make_cpp_type(metadata, config, tdi, ty_data, generic_inst)
-                        .expect("Failed to make nested type");
+                }) {
+                    Ok(Some(new_cpp_type)) => new_cpp_type,
+                    Ok(None) => {
+                        warn!(
+                            "Unable to make nested type {}",
+                            ty_def.full_name(metadata.metadata, true)
+                        );
+                        return None;
+                    }
+                    Err(e) => {
+                        let full_name = ty_def.full_name(metadata.metadata, true);
+                        warn!("Failed to make nested type {full_name}: {e}");
+
+                        self.failures.push(TypeFailure {
+                            tag: ty_data,
+                            full_name,
+                            stage: TypeFailureStage::Make,
+                            reason: e.to_string(),
+                        });
+                        return None;
+                    }
+                };
 
                 let context = self.get_context_mut(ty_data).unwrap();
                 // self.alias_type_to_context(new_cpp_type.self_tag, context_root_tag, true);
@@ -340,14 +387,30 @@ impl CppContextCollection {
             cpptype
         });
 
-        let mut new_cpp_type = CppType::make_cpp_type(
-            metadata,
-            config,
-            tdi,
-            generic_class_ty_data,
-            Some(&generic_inst.types),
-        )
-        .expect("Failed to make generic type");
+        let mut new_cpp_type = match catch_panic(|| {
+            CppType::make_cpp_type(
+                metadata,
+                config,
+                tdi,
+                generic_class_ty_data,
+                Some(&generic_inst.types),
+            )
+        }) {
+            Ok(Some(new_cpp_type)) => new_cpp_type,
+            Ok(None) => return None,
+            Err(e) => {
+                let full_name = ty_def.full_name(metadata.metadata, true);
+                warn!("Failed to make generic instantiation of {full_name}: {e}");
+
+                self.failures.push(TypeFailure {
+                    tag: generic_class_ty_data,
+                    full_name,
+                    stage: TypeFailureStage::Make,
+                    reason: e.to_string(),
+                });
+                return None;
+            }
+        };
         new_cpp_type.self_tag = generic_class_ty_data;
         self.alias_type_to_context(new_cpp_type.self_tag, context_root_tag, true, false);
 
@@ -508,7 +571,7 @@ impl CppContextCollection {
                 .contains_key(&CppType::get_tag_tdi(type_tag)),
             "Cannot create context for nested type",
         );
-        let context_root_tag = self.get_context_root_tag(type_tag.into());
+        let context_root_tag = self.get_context_root_tag(CppType::get_tag_tdi(type_tag).into());
 
         if self.filling_types.contains(&context_root_tag) {
             panic!("Currently filling type {context_root_tag:?}, cannot fill")
@@ -525,7 +588,14 @@ impl CppContextCollection {
         }
 
         let tdi = CppType::get_cpp_tag_tdi(context_root_tag);
-        let context = CppContext::make(metadata, config, tdi, context_root_tag, generic_inst);
+        let context = CppContext::make(
+            metadata,
+            config,
+            tdi,
+            context_root_tag,
+            generic_inst,
+            &mut self.failures,
+        );
         // Now do children
         for cpp_type in context.typedef_types.values() {
             self.alias_nested_types(cpp_type, cpp_type.self_tag, false);
@@ -629,6 +699,117 @@ impl CppContextCollection {
             .get_mut(&self.get_context_root_tag(context_tag))
     }
 
+    ///
+    /// Make the contexts of all types, root types first then nested types
+    ///
+    pub fn make_all(&mut self, metadata: &Metadata, config: &GenerationConfig) {
+        self.make_root_types(metadata, config);
+        self.make_nested_types(metadata, config);
+    }
+
+    pub fn make_root_types(&mut self, metadata: &Metadata, config: &GenerationConfig) {
+        let type_defs = metadata.metadata.global_metadata.type_definitions.as_vec();
+        let total = type_defs.len();
+
+        info!("Making types");
+        for tdi_u64 in 0..total {
+            let tdi = TypeDefinitionIndex::new(tdi_u64 as u32);
+
+            let ty_def = &metadata.metadata.global_metadata.type_definitions[tdi];
+
+            if ty_def.declaring_type_index != u32::MAX {
+                continue;
+            }
+
+            trace!(
+                "Making types {:.4}% ({tdi_u64}/{total})",
+                (tdi_u64 as f64 / total as f64 * 100.0)
+            );
+            self.make_from(metadata, config, TypeData::TypeDefinitionIndex(tdi), None);
+            self.alias_nested_types_il2cpp(
+                tdi,
+                CppTypeTag::TypeDefinitionIndex(tdi),
+                metadata,
+                false,
+            );
+        }
+    }
+
+    pub fn make_nested_types(&mut self, metadata: &Metadata, config: &GenerationConfig) {
+        let type_defs = metadata.metadata.global_metadata.type_definitions.as_vec();
+        let total = type_defs.len();
+
+        info!("Making nested types");
+        for tdi_u64 in 0..total {
+            let tdi = TypeDefinitionIndex::new(tdi_u64 as u32);
+
+            let ty_def = &metadata.metadata.global_metadata.type_definitions[tdi];
+
+            if ty_def.declaring_type_index == u32::MAX {
+                continue;
+            }
+
+            trace!(
+                "Making nested types {:.4}% ({tdi_u64}/{total})",
+                (tdi_u64 as f64 / total as f64 * 100.0)
+            );
+            self.make_nested_from(metadata, config, tdi, None);
+        }
+    }
+
+    ///
+    /// Fill the generic method instantiations of all types
+    ///
+    pub fn fill_generic_method_insts(
+        &mut self,
+        metadata: &mut Metadata,
+        config: &GenerationConfig,
+    ) {
+        let metadata_registration = metadata.metadata_registration;
+        let total = metadata_registration.generic_method_table.len() as f64;
+
+        info!("Filling generic methods!");
+        for (i, generic_class) in metadata_registration
+            .generic_method_table
+            .iter()
+            .enumerate()
+        {
+            trace!(
+                "Filling generic method instantiations {:.4}% ({i}/{total})",
+                (i as f64 / total * 100.0)
+            );
+            let method_spec = metadata_registration
+                .method_specs
+                .get(generic_class.generic_method_index as usize)
+                .unwrap();
+
+            self.fill_generic_method_inst(method_spec, metadata, config);
+        }
+    }
+
+    ///
+    /// Fill all types, handlers should be registered before this
+    ///
+    pub fn fill_all(&mut self, metadata: &Metadata, config: &GenerationConfig) {
+        info!("Filling types");
+        let total = metadata
+            .metadata
+            .global_metadata
+            .type_definitions
+            .as_vec()
+            .len();
+        for tdi_u64 in 0..total {
+            let tdi = TypeDefinitionIndex::new(tdi_u64 as u32);
+
+            trace!(
+                "Filling type {:.4} ({tdi_u64}/{total})",
+                (tdi_u64 as f64 / total as f64 * 100.0)
+            );
+
+            self.fill(metadata, config, CppTypeTag::TypeDefinitionIndex(tdi));
+        }
+    }
+
     pub fn new() -> CppContextCollection {
         CppContextCollection {
             all_contexts: Default::default(),
@@ -637,8 +818,14 @@ impl CppContextCollection {
             alias_nested_type_to_parent: Default::default(),
             alias_context: Default::default(),
             borrowing_types: Default::default(),
+            failures: Default::default(),
         }
     }
+
+    /// Types that failed to make or fill
+    pub fn failures(&self) -> &[TypeFailure] {
+        &self.failures
+    }
     pub fn get(&self) -> &HashMap<CppTypeTag, CppContext> {
         &self.all_contexts
     }
@@ -663,13 +850,13 @@ impl CppContextCollection {
             })
     }
 
-    pub fn write_namespace_headers(&self) -> color_eyre::Result<()> {
+    pub fn write_namespace_headers(&self, config: &GenerationConfig) -> color_eyre::Result<()> {
         self.all_contexts
             .iter()
             .into_group_map_by(|(_, c)| c.fundamental_path.parent())
             .into_iter()
             .try_for_each(|(dir, contexts)| -> color_eyre::Result<()> {
-                let namespace = if dir.unwrap() == STATIC_CONFIG.header_path {
+                let namespace = if dir.unwrap() == config.header_path {
                     "GlobalNamespace"
                 } else {
                     dir.unwrap().file_name().unwrap().to_str().unwrap()
@@ -691,7 +878,7 @@ impl CppContextCollection {
                     // add includes
                     .map(|(_, c)| {
                         let stripped_path =
-                            diff_paths(&c.fundamental_path, &STATIC_CONFIG.header_path).unwrap();
+                            diff_paths(&c.fundamental_path, &config.header_path).unwrap();
 
                         let stripped_path_friendly = if cfg!(windows) {
                             stripped_path.to_string_lossy().replace('\\', "/")
@@ -722,6 +909,16 @@ impl CppContextCollection {
                 "
                 )?;
                 writeln!(file, "#pragma once")?;
+
+                // fails to compile if this namespace is from an older generation than the rest
+                writeln!(file, "// Generated from {}", config.fingerprint.describe())?;
+                writeln!(file, "#include \"{VERSION_HEADER}\"")?;
+                writeln!(
+                    file,
+                    "static_assert({}, \"{namespace} was generated from different inputs than cordl_version.hpp\");",
+                    config.fingerprint.static_assert_condition()
+                )?;
+
                 file.write_all(str.as_bytes())?;
 
                 writeln!(file)?;
@@ -16,6 +16,7 @@ use super::{
     cpp_type_tag::CppTypeTag,
     members::{CppForwardDeclare, CppInclude, CppMember, CppNonMember, CppTemplate},
     offsets::SizeInfo,
+    runtime_profile::RuntimeProfile,
     writer::{CppWriter, Sortable, Writable},
 };
 
@@ -23,7 +24,6 @@ pub const CORDL_TYPE_MACRO: &str = "CORDL_TYPE";
 pub const __CORDL_IS_VALUE_TYPE: &str = "__IL2CPP_IS_VALUE_TYPE";
 pub const __CORDL_BACKING_ENUM_TYPE: &str = "__CORDL_BACKING_ENUM_TYPE";
 
-pub const CORDL_REFERENCE_TYPE_CONSTRAINT: &str = "::il2cpp_utils::il2cpp_reference_type";
 pub const CORDL_NUM_ENUM_TYPE_CONSTRAINT: &str = "::cordl_internals::is_or_is_backed_by";
 pub const CORDL_METHOD_HELPER_NAMESPACE: &str = "::cordl_internals";
 
@@ -84,6 +84,12 @@ pub struct CppType {
 
     pub declarations: Vec<Rc<CppMember>>,
     pub implementations: Vec<Rc<CppMember>>,
+    /// Implementations written to the type's `.cpp` source file instead of its headers
+    pub source_implementations: Vec<Rc<CppMember>>,
+    /// Methods resolved through the central method table, see `method_table.rs`
+    pub method_table_entries: Vec<MethodIndex>,
+    /// C# name and generated offset of each instance field, checked at runtime by `verify.rs`
+    pub instance_field_offsets: Vec<(String, u32)>,
     /// Outside of the class declaration
     /// Move to CsType/CppType?
     pub nonmember_implementations: Vec<Rc<CppNonMember>>,
@@ -95,6 +101,8 @@ pub struct CppType {
     pub requirements: CppTypeRequirements,
 
     pub inherit: Vec<String>,
+    /// Interfaces the type implements, as resolved by make_interfaces
+    pub interfaces: Vec<NameComponents>,
     pub cpp_template: Option<CppTemplate>, // Names of templates e.g T, TKey etc.
 
     /// contains the array of generic Il2CppType indexes
@@ -108,11 +116,8 @@ pub struct CppType {
 }
 
 impl CppTypeRequirements {
-    pub fn need_wrapper(&mut self) {
-        self.add_def_include(
-            None,
-            CppInclude::new_exact("beatsaber-hook/shared/utils/base-wrapper-type.hpp"),
-        );
+    pub fn need_wrapper(&mut self, runtime: &RuntimeProfile) {
+        self.add_def_include(None, CppInclude::new_exact(&runtime.includes.base_wrapper));
     }
     pub fn needs_int_include(&mut self) {
         self.add_def_include(None, CppInclude::new_system("cstdint"));
@@ -123,38 +128,29 @@ impl CppTypeRequirements {
     pub fn needs_math_include(&mut self) {
         self.add_def_include(None, CppInclude::new_system("cmath"));
     }
-    pub fn needs_stringw_include(&mut self) {
-        self.add_def_include(
-            None,
-            CppInclude::new_exact("beatsaber-hook/shared/utils/typedefs-string.hpp"),
-        );
+    pub fn needs_limits_include(&mut self) {
+        self.add_def_include(None, CppInclude::new_system("limits"));
     }
-    pub fn needs_arrayw_include(&mut self) {
-        self.add_def_include(
-            None,
-            CppInclude::new_exact("beatsaber-hook/shared/utils/typedefs-array.hpp"),
-        );
+    pub fn needs_string_view_include(&mut self) {
+        self.add_def_include(None, CppInclude::new_system("string_view"));
+    }
+    pub fn needs_stringw_include(&mut self, runtime: &RuntimeProfile) {
+        self.add_def_include(None, CppInclude::new_exact(&runtime.includes.string));
+    }
+    pub fn needs_arrayw_include(&mut self, runtime: &RuntimeProfile) {
+        self.add_def_include(None, CppInclude::new_exact(&runtime.includes.array));
     }
 
-    pub fn needs_byref_include(&mut self) {
-        self.add_def_include(
-            None,
-            CppInclude::new_exact("beatsaber-hook/shared/utils/byref.hpp"),
-        );
+    pub fn needs_byref_include(&mut self, runtime: &RuntimeProfile) {
+        self.add_def_include(None, CppInclude::new_exact(&runtime.includes.byref));
     }
 
-    pub fn needs_enum_include(&mut self) {
-        self.add_def_include(
-            None,
-            CppInclude::new_exact("beatsaber-hook/shared/utils/enum-type.hpp"),
-        );
+    pub fn needs_enum_include(&mut self, runtime: &RuntimeProfile) {
+        self.add_def_include(None, CppInclude::new_exact(&runtime.includes.enum_type));
     }
 
-    pub fn needs_value_include(&mut self) {
-        self.add_def_include(
-            None,
-            CppInclude::new_exact("beatsaber-hook/shared/utils/value-type.hpp"),
-        );
+    pub fn needs_value_include(&mut self, runtime: &RuntimeProfile) {
+        self.add_def_include(None, CppInclude::new_exact(&runtime.includes.value_type));
     }
 }
 
@@ -274,6 +270,36 @@ impl CppType {
         Ok(())
     }
 
+    /// Writes the source implementations and out of line method info lookups, including nested types
+    pub fn write_source(&self, writer: &mut super::writer::CppWriter) -> color_eyre::Result<()> {
+        self.nonmember_implementations
+            .iter()
+            .filter_map(|n| match n.as_ref() {
+                CppNonMember::SizeStruct(s) if s.out_of_line => Some(s),
+                _ => None,
+            })
+            .try_for_each(|s| s.write_source(writer))?;
+
+        self.source_implementations
+            .iter()
+            .sorted_by(|a, b| a.sort_level().cmp(&b.sort_level()))
+            .try_for_each(|d| d.write(writer))?;
+
+        self.nested_types
+            .iter()
+            .try_for_each(|(_tag, n)| n.write_source(writer))
+    }
+
+    /// Whether [`CppType::write_source`] writes anything
+    pub fn has_source(&self) -> bool {
+        !self.source_implementations.is_empty()
+            || self
+                .nonmember_implementations
+                .iter()
+                .any(|n| matches!(n.as_ref(), CppNonMember::SizeStruct(s) if s.out_of_line))
+            || self.nested_types.values().any(|n| n.has_source())
+    }
+
     fn write_def_internal(
         &self,
         writer: &mut super::writer::CppWriter,
@@ -3,6 +3,7 @@ use brocolib;
 use brocolib::runtime_metadata::TypeData;
 
 use brocolib::global_metadata::TypeDefinitionIndex;
+use color_eyre::eyre::{bail, eyre, Result};
 
 // TODO:
 /// Indices into the [`Il2CppMetadataRegistration::generic_insts`] field
@@ -29,11 +30,13 @@ impl From<TypeDefinitionIndex> for CppTypeTag {
     }
 }
 
-impl From<TypeData> for CppTypeTag {
-    fn from(value: TypeData) -> Self {
+impl TryFrom<TypeData> for CppTypeTag {
+    type Error = color_eyre::Report;
+
+    fn try_from(value: TypeData) -> Result<Self> {
         match value {
-            TypeData::TypeDefinitionIndex(i) => i.into(),
-            _ => panic!("Can't use {value:?} for CppTypeTag"),
+            TypeData::TypeDefinitionIndex(i) => Ok(i.into()),
+            _ => bail!("Can't use {value:?} for CppTypeTag"),
         }
     }
 }
@@ -43,7 +46,6 @@ impl From<CppTypeTag> for TypeData {
         match value {
             CppTypeTag::TypeDefinitionIndex(i) => TypeData::TypeDefinitionIndex(i),
             CppTypeTag::GenericInstantiation(gen) => TypeData::GenericClassIndex(gen.inst), // TODO:?
-            _ => panic!("Can't go from {value:?} to TypeData"),
         }
     }
 }
@@ -53,7 +55,6 @@ impl From<CppTypeTag> for TypeDefinitionIndex {
         match value {
             CppTypeTag::TypeDefinitionIndex(i) => i,
             CppTypeTag::GenericInstantiation(generic_inst) => generic_inst.tdi,
-            _ => panic!("Type is not a TDI! {value:?}"),
         }
     }
 }
@@ -62,7 +63,7 @@ impl CppTypeTag {
     pub fn from_generic_class_index(
         generic_class_idx: usize,
         metadata: &brocolib::Metadata,
-    ) -> Self {
+    ) -> Result<Self> {
         let generic_class = &metadata
             .runtime_metadata
             .metadata_registration
@@ -72,24 +73,24 @@ impl CppTypeTag {
             metadata.runtime_metadata.metadata_registration.types[generic_class.type_index];
         // Unwrap
         let TypeData::TypeDefinitionIndex(tdi) = ty.data else {
-            panic!("No TDI for generic inst!")
+            bail!("No TDI for generic inst {generic_class_idx}!")
         };
 
-        Self::GenericInstantiation(GenericInstantiation {
+        Ok(Self::GenericInstantiation(GenericInstantiation {
             tdi,
             inst: generic_class
                 .context
                 .class_inst_idx
-                .expect("Not a generic class inst idx"),
-        })
+                .ok_or_else(|| eyre!("Not a generic class inst idx {generic_class_idx}"))?,
+        }))
     }
-    pub fn from_type_data(type_data: TypeData, metadata: &brocolib::Metadata) -> Self {
+    pub fn from_type_data(type_data: TypeData, metadata: &brocolib::Metadata) -> Result<Self> {
         match type_data {
-            TypeData::TypeDefinitionIndex(tdi) => tdi.into(),
+            TypeData::TypeDefinitionIndex(tdi) => Ok(tdi.into()),
             TypeData::GenericClassIndex(generic_class_idx) => {
                 Self::from_generic_class_index(generic_class_idx, metadata)
             }
-            _ => todo!(),
+            _ => bail!("Can't use {type_data:?} for CppTypeTag"),
         }
     }
 
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
 use crate::generate::cpp_type::CppType;
+use crate::generate::cs_type::escape_cpp_literal;
+use crate::generate::cs_type::CLASS_INIT_METHOD_NAME;
 use crate::generate::cs_type::CORDL_ACCESSOR_FIELD_PREFIX;
 use crate::generate::members::CppLine;
 use crate::generate::members::CppNestedUnion;
@@ -16,6 +18,7 @@ use brocolib::runtime_metadata::Il2CppTypeEnum;
 
 use brocolib::global_metadata::TypeDefinitionIndex;
 
+use super::config::GenerationConfig;
 use super::context_collection::CppContextCollection;
 use super::cpp_type::CORDL_METHOD_HELPER_NAMESPACE;
 use super::cpp_type_tag::CppTypeTag;
@@ -33,6 +36,7 @@ use super::members::CppPropertyDecl;
 use super::members::CppStaticAssert;
 use super::members::CppTemplate;
 use super::metadata::Metadata;
+use super::offsets;
 use super::type_extensions::Il2CppTypeEnumExtensions;
 use super::type_extensions::TypeDefinitionExtensions;
 use super::type_extensions::TypeExtentions;
@@ -48,6 +52,8 @@ pub struct FieldInfo<'a> {
     pub is_pointer: bool,
 
     pub offset: Option<u32>,
+    /// Offset into the class static data for static fields, as found in the runtime offset table
+    pub static_offset: Option<u32>,
     pub size: usize,
 }
 
@@ -63,10 +69,14 @@ impl<'a> FieldInfoSet<'a> {
     }
 }
 
+/// The offset il2cpp gives `[ThreadStatic]` fields, their data is stored per thread
+const THREAD_STATIC_FIELD_OFFSET: u32 = u32::MAX;
+
 pub fn handle_static_fields(
     cpp_type: &mut CppType,
     fields: &[FieldInfo],
     metadata: &Metadata,
+    config: &GenerationConfig,
     tdi: TypeDefinitionIndex,
 ) {
     let t = CppType::get_type_definition(metadata, tdi);
@@ -90,14 +100,30 @@ pub fn handle_static_fields(
         // ref type instance fields are specially named because the field getters are supposed to be used
         let f_cpp_name = field_info.cpp_field.cpp_name.clone();
 
-        let klass_resolver = cpp_type.classof_cpp_name();
+        let klass_resolver = cpp_type.classof_cpp_name(config);
 
-        let getter_call =
-                format!("return {CORDL_METHOD_HELPER_NAMESPACE}::getStaticField<{field_ty_cpp_name}, \"{f_name}\", {klass_resolver}>();");
+        // thread static data can only be found by the runtime,
+        // so these always go through the FieldInfo, whatever the config says
+        let is_thread_static = field_info.static_offset == Some(THREAD_STATIC_FIELD_OFFSET);
 
         let setter_var_name = "value";
-        let setter_call =
-                format!("{CORDL_METHOD_HELPER_NAMESPACE}::setStaticField<{field_ty_cpp_name}, \"{f_name}\", {klass_resolver}>(std::forward<{field_ty_cpp_name}>({setter_var_name}));");
+        let (getter_call, setter_call) = match direct_static_field_offset(
+            cpp_type,
+            field_info,
+            metadata,
+            config,
+            tdi,
+        ) {
+            // the static data is only valid once the static constructor ran
+            Some(static_offset) => (
+                format!("{CLASS_INIT_METHOD_NAME}();\nreturn {CORDL_METHOD_HELPER_NAMESPACE}::getStaticFieldAtOffset<{field_ty_cpp_name}, 0x{static_offset:x}, \"{f_name}\", {klass_resolver}>();"),
+                format!("{CLASS_INIT_METHOD_NAME}();\n{CORDL_METHOD_HELPER_NAMESPACE}::setStaticFieldAtOffset<{field_ty_cpp_name}, 0x{static_offset:x}, \"{f_name}\", {klass_resolver}>(std::forward<{field_ty_cpp_name}>({setter_var_name}));"),
+            ),
+            None => (
+                format!("return {CORDL_METHOD_HELPER_NAMESPACE}::getStaticField<{field_ty_cpp_name}, \"{f_name}\", {klass_resolver}>();"),
+                format!("{CORDL_METHOD_HELPER_NAMESPACE}::setStaticField<{field_ty_cpp_name}, \"{f_name}\", {klass_resolver}>(std::forward<{field_ty_cpp_name}>({setter_var_name}));"),
+            ),
+        };
 
         // don't get a template that has no names
         let useful_template =
@@ -185,9 +211,10 @@ pub fn handle_static_fields(
             getter: getter_decl.cpp_name.clone().into(),
             setter: setter_decl.cpp_name.clone().into(),
             indexable: false,
-            brief_comment: Some(format!(
-                "Field {f_name}, offset 0x{f_offset:x}, size 0x{f_size:x} "
-            )),
+            brief_comment: Some(match is_thread_static {
+                true => format!("Thread static field {f_name}, size 0x{f_size:x} "),
+                false => format!("Field {f_name}, offset 0x{f_offset:x}, size 0x{f_size:x} "),
+            }),
         };
 
         // only push accessors if declaring ref type, or if static field
@@ -215,6 +242,43 @@ pub fn handle_static_fields(
     }
 }
 
+/// The offset of a static field in its class static data,
+/// if [`GenerationConfig::static_field_offsets`] is enabled and the offset can be trusted
+fn direct_static_field_offset(
+    cpp_type: &CppType,
+    field_info: &FieldInfo,
+    metadata: &Metadata,
+    config: &GenerationConfig,
+    tdi: TypeDefinitionIndex,
+) -> Option<u32> {
+    if !config.static_field_offsets {
+        return None;
+    }
+
+    // every generic instantiation has its own static data,
+    // the offsets of the generic definition don't apply to it
+    if cpp_type.cpp_template.is_some() || cpp_type.generic_instantiations_args_types.is_some() {
+        return None;
+    }
+
+    let static_offset = field_info.static_offset?;
+    if static_offset == THREAD_STATIC_FIELD_OFFSET {
+        return None;
+    }
+
+    let static_fields_size = offsets::get_size_of_type_table(metadata, tdi)?.static_fields_size;
+    if static_offset as usize + field_info.size > static_fields_size as usize {
+        warn!(
+            "Static field {} of {} at 0x{static_offset:x} is outside of the 0x{static_fields_size:x} bytes of static data, accessing it by name",
+            field_info.field.name(metadata.metadata),
+            cpp_type.cpp_name_components.combine_all()
+        );
+        return None;
+    }
+
+    Some(static_offset)
+}
+
 pub(crate) fn handle_const_fields(
     cpp_type: &mut CppType,
     fields: &[FieldInfo],
@@ -249,12 +313,20 @@ pub(crate) fn handle_const_fields(
 
         let def_value = def_value.expect("Constant with no default value?");
 
-        match f_type.ty.is_primitive_builtin() {
+        // `const string` fields are `::std::u16string_view` literals, so like primitives
+        // they are defined in class instead of as an out of class impl or getter
+        let in_class = match f_type.ty {
+            Il2CppTypeEnum::String => true,
+            ty => ty.is_primitive_builtin(),
+        };
+
+        match in_class {
             false => {
                 // other type
                 let field_decl = CppFieldDecl {
                     instance: false,
-                    readonly: f_type.is_constant(),
+                    // decimal constants are readonly statics rather than literals
+                    readonly: true,
                     value: None,
                     const_expr: false,
                     brief_comment: Some(format!("Field {f_name} value: {def_value}")),
@@ -268,17 +340,24 @@ pub(crate) fn handle_const_fields(
                     ..field_decl.clone().into()
                 };
 
-                // get enum type to include impl
-                // this is needed since the enum constructor is not defined
+                // get enum or decimal type to include impl
+                // this is needed since the enum and value type constructors are not defined
                 // in the declaration
                 // TODO: Make enum ctors inline defined
-                if f_type.valuetype && f_type.ty == Il2CppTypeEnum::Valuetype {
-                    let field_cpp_tag: CppTypeTag =
-                        CppTypeTag::from_type_data(f_type.data, metadata.metadata);
+                if f_type.valuetype
+                    && f_type.ty == Il2CppTypeEnum::Valuetype
+                    && let Ok(field_cpp_tag) =
+                        CppTypeTag::from_type_data(f_type.data, metadata.metadata)
+                {
                     let field_cpp_td_tag: CppTypeTag = field_cpp_tag.get_tdi().into();
                     let field_cpp_type = ctx_collection.get_cpp_type(field_cpp_td_tag);
 
-                    if field_cpp_type.is_some_and(|f| f.is_enum_type) {
+                    let own_context = ctx_collection.get_context_root_tag(field_cpp_td_tag)
+                        == ctx_collection.get_context_root_tag(cpp_type.self_tag);
+
+                    if !own_context
+                        && field_cpp_type.is_some_and(|f| f.is_enum_type || f.is_value_type)
+                    {
                         let field_cpp_context = ctx_collection
                             .get_context(field_cpp_td_tag)
                             .expect("No context for cpp enum type");
@@ -298,11 +377,11 @@ pub(crate) fn handle_const_fields(
                     .push(CppMember::FieldImpl(field_impl).into());
             }
             true => {
-                // primitive type
+                // primitive type or string
                 let field_decl = CppFieldDecl {
                     instance: false,
                     const_expr: true,
-                    readonly: f_type.is_constant(),
+                    readonly: true,
 
                     brief_comment: Some(format!(
                         "Field {f_name} offset 0x{f_offset:x} size 0x{f_size:x}"
@@ -487,6 +566,47 @@ pub(crate) fn handle_valuetype_fields(
     } else {
         handle_instance_fields(cpp_type, fields, metadata, tdi);
     }
+
+    add_field_reflection(cpp_type, fields, metadata);
+}
+
+/// Describes the instance fields of a value type for `::cordl_internals::value_type_fields`
+fn add_field_reflection(cpp_type: &mut CppType, fields: &[FieldInfo], metadata: &Metadata) {
+    // generic offsets depend on the instantiation
+    if cpp_type.is_enum_type || cpp_type.cpp_template.is_some() {
+        return;
+    }
+
+    let descriptors = fields
+        .iter()
+        .filter(|f| !f.is_constant && !f.is_static)
+        .filter_map(|f| {
+            let offset = f.offset?;
+            let f_name = escape_cpp_literal(f.field.name(metadata.metadata));
+            let f_ty = &f.cpp_field.field_ty;
+
+            Some(format!(
+                "::cordl_internals::ValueTypeField<{f_ty}>{{\"{f_name}\", 0x{offset:x}, 0x{:x}}}",
+                f.size
+            ))
+        })
+        .join(", ");
+
+    let fields_decl = CppFieldDecl {
+        cpp_name: "__CORDL_FIELDS".to_string(),
+        field_ty: "::std::tuple".to_string(),
+        offset: u32::MAX,
+        instance: false,
+        readonly: true,
+        const_expr: true,
+        value: Some(descriptors),
+        brief_comment: Some("Instance fields with their offset and size".to_string()),
+        is_private: false,
+    };
+
+    cpp_type
+        .declarations
+        .push(CppMember::FieldDecl(fields_decl).into());
 }
 
 // create prop and field declaration from passed field info
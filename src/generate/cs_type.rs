@@ -17,6 +17,7 @@ use brocolib::{
     runtime_metadata::{Il2CppMethodSpec, Il2CppType, Il2CppTypeEnum, TypeData},
 };
 use byteorder::{LittleEndian, ReadBytesExt};
+use color_eyre::eyre::{bail, eyre, Result};
 
 use itertools::Itertools;
 
@@ -27,11 +28,10 @@ use crate::{
 };
 
 use super::{
-    config::GenerationConfig,
+    config::{GenerationConfig, MethodLookup},
     context_collection::CppContextCollection,
     cpp_type::{
-        CppType, CppTypeRequirements, CORDL_NUM_ENUM_TYPE_CONSTRAINT,
-        CORDL_REFERENCE_TYPE_CONSTRAINT, __CORDL_BACKING_ENUM_TYPE,
+        CppType, CppTypeRequirements, CORDL_NUM_ENUM_TYPE_CONSTRAINT, __CORDL_BACKING_ENUM_TYPE,
     },
     cpp_type_tag::CppTypeTag,
     cs_fields::{
@@ -59,17 +59,12 @@ pub const VALUE_TYPE_SIZE_OFFSET: u32 = 0x10;
 pub const VALUE_TYPE_WRAPPER_SIZE: &str = "__IL2CPP_VALUE_TYPE_SIZE";
 pub const REFERENCE_TYPE_WRAPPER_SIZE: &str = "__IL2CPP_REFERENCE_TYPE_SIZE";
 pub const REFERENCE_TYPE_FIELD_SIZE: &str = "__fields";
-pub const REFERENCE_WRAPPER_INSTANCE_NAME: &str = "::bs_hook::Il2CppWrapperType::instance";
 
-pub const VALUE_WRAPPER_TYPE: &str = "::bs_hook::ValueType";
-pub const ENUM_WRAPPER_TYPE: &str = "::bs_hook::EnumType";
 pub const INTERFACE_WRAPPER_TYPE: &str = "::cordl_internals::InterfaceW";
 pub const IL2CPP_OBJECT_TYPE: &str = "Il2CppObject";
 pub const CORDL_NO_INCLUDE_IMPL_DEFINE: &str = "CORDL_NO_IMPL_INCLUDE";
 pub const CORDL_ACCESSOR_FIELD_PREFIX: &str = "___";
-
-pub const ENUM_PTR_TYPE: &str = "::bs_hook::EnumPtr";
-pub const VT_PTR_TYPE: &str = "::bs_hook::VTPtr";
+pub const CLASS_INIT_METHOD_NAME: &str = "EnsureClassInit";
 
 const SIZEOF_IL2CPP_OBJECT: u32 = 0x10;
 
@@ -149,7 +144,7 @@ pub trait CSType: Sized {
         tdi: TypeDefinitionIndex,
         tag: CppTypeTag,
         generic_inst_types: Option<&Vec<usize>>,
-    ) -> Option<CppType> {
+    ) -> Result<Option<CppType>> {
         // let iface = metadata.interfaces.get(t.interfaces_start);
         // Then, handle interfaces
 
@@ -180,7 +175,7 @@ pub trait CSType: Sized {
         if metadata.blacklisted_types.contains(&tdi) {
             info!("Skipping {full_name} ({tdi:?}) because it's blacklisted");
 
-            return None;
+            return Ok(None);
         }
 
         // all nested types are unnested
@@ -231,7 +226,10 @@ pub trait CSType: Sized {
 
             declarations: Default::default(),
             implementations: Default::default(),
+            source_implementations: Default::default(),
             nonmember_implementations: Default::default(),
+            method_table_entries: Default::default(),
+            instance_field_offsets: Default::default(),
             nonmember_declarations: Default::default(),
 
             is_value_type: t.is_value_type(),
@@ -240,6 +238,7 @@ pub trait CSType: Sized {
             requirements: Default::default(),
 
             inherit: Default::default(),
+            interfaces: Default::default(),
             is_interface: t.is_interface(),
             cpp_template,
 
@@ -263,7 +262,7 @@ pub trait CSType: Sized {
                 .metadata_registration
                 .types[t.declaring_type_index as usize];
 
-            let declaring_tag = CppTypeTag::from_type_data(declaring_ty.data, metadata.metadata);
+            let declaring_tag = CppTypeTag::from_type_data(declaring_ty.data, metadata.metadata)?;
             let declaring_tdi: TypeDefinitionIndex = declaring_tag.into();
             let declaring_td = &metadata.metadata.global_metadata.type_definitions[declaring_tdi];
             let combined_name = cpptype
@@ -280,10 +279,14 @@ pub trait CSType: Sized {
             cpptype.cpp_name_components.name = config.sanitize_to_cpp_name(&combined_name);
         }
 
+        if let Some(name) = metadata.custom_type_names.get(&tdi) {
+            cpptype.cpp_name_components.name = config.sanitize_to_cpp_name(name);
+        }
+
         if t.parent_index == u32::MAX {
             if !t.is_interface() && t.full_name(metadata.metadata, true) != "System.Object" {
                 info!("Skipping type: {ns}::{name} because it has parent index: {} and is not an interface!", t.parent_index);
-                return None;
+                return Ok(None);
             }
         } else if metadata
             .metadata_registration
@@ -291,10 +294,10 @@ pub trait CSType: Sized {
             .get(t.parent_index as usize)
             .is_none()
         {
-            panic!("NO PARENT! But valid index found: {}", t.parent_index);
+            bail!("NO PARENT! But valid index found: {}", t.parent_index);
         }
 
-        Some(cpptype)
+        Ok(Some(cpptype))
     }
 
     fn fill_from_il2cpp(
@@ -302,28 +305,28 @@ pub trait CSType: Sized {
         metadata: &Metadata,
         config: &GenerationConfig,
         ctx_collection: &CppContextCollection,
-    ) {
+    ) -> Result<()> {
         if self.get_cpp_type().is_stub {
             // Do not fill stubs
-            return;
+            return Ok(());
         }
 
         let tdi: TypeDefinitionIndex = self.get_cpp_type().self_tag.into();
 
         let t = &metadata.metadata.global_metadata.type_definitions[tdi];
 
-        self.make_generics_args(metadata, ctx_collection, tdi);
-        self.make_parents(metadata, ctx_collection, tdi);
+        self.make_generics_args(metadata, ctx_collection, config, tdi);
+        self.make_parents(metadata, ctx_collection, config, tdi)?;
         self.make_interfaces(metadata, ctx_collection, config, tdi);
 
         // we depend on parents and generic args here
         // default ctor
         if t.is_value_type() || t.is_enum_type() {
             self.create_valuetype_constructor(metadata, ctx_collection, config, tdi);
-            self.create_valuetype_field_wrapper();
+            self.create_valuetype_field_wrapper()?;
             if t.is_enum_type() {
-                self.create_enum_wrapper(metadata, ctx_collection, tdi);
-                self.create_enum_backing_type_constant(metadata, ctx_collection, tdi);
+                self.create_enum_wrapper(metadata, ctx_collection, config, tdi);
+                self.create_enum_backing_type_constant(metadata, ctx_collection, config, tdi);
             }
             self.add_default_ctor(false);
         } else if t.is_interface() {
@@ -340,15 +343,17 @@ pub trait CSType: Sized {
         }
 
         if !t.is_interface() {
-            self.create_size_assert();
+            self.create_size_assert()?;
         }
 
         self.add_type_index_member();
+        self.add_class_init_method(config);
 
         self.make_nested_types(metadata, ctx_collection, config, tdi);
-        self.make_fields(metadata, ctx_collection, config, tdi);
+        self.make_fields(metadata, ctx_collection, config, tdi)?;
         self.make_properties(metadata, ctx_collection, config, tdi);
         self.make_methods(metadata, config, ctx_collection, tdi);
+        self.create_delegate_helpers(metadata, config, tdi);
 
         if !t.is_interface() {
             self.create_size_padding(metadata, tdi);
@@ -357,6 +362,64 @@ pub trait CSType: Sized {
         if let Some(func) = metadata.custom_type_handler.get(&tdi) {
             func(self.get_mut_cpp_type())
         }
+
+        Ok(())
+    }
+
+    ///
+    /// Downgrade a type into an opaque type that only keeps its base type and size,
+    /// used for types that failed to make or fill
+    ///
+    fn make_opaque_stub(&mut self, reason: &str) {
+        let cpp_type = self.get_mut_cpp_type();
+
+        cpp_type.declarations.clear();
+        cpp_type.implementations.clear();
+        cpp_type.nonmember_declarations.clear();
+        cpp_type.nonmember_implementations.clear();
+        cpp_type.prefix_comments.push(format!(
+            "Opaque type, {}",
+            reason.lines().next().unwrap_or_default()
+        ));
+
+        if cpp_type.is_interface {
+            return;
+        }
+
+        let is_value_type = cpp_type.is_value_type || cpp_type.is_enum_type;
+        if !is_value_type && cpp_type.inherit.is_empty() {
+            cpp_type.inherit.push(IL2CPP_OBJECT_TYPE.to_string());
+        }
+
+        let Some(size) = cpp_type.size_info.as_ref().map(|s| s.instance_size) else {
+            return;
+        };
+
+        let opaque_size = match cpp_type.inherit.first() {
+            Some(base_type) if !is_value_type => format!("0x{size:x} - sizeof({base_type})"),
+            _ => format!("0x{size:x}"),
+        };
+
+        cpp_type.declarations.push(
+            CppMember::FieldDecl(CppFieldDecl {
+                cpp_name: format!("_cordl_opaque_data[{opaque_size}]"),
+                field_ty: "uint8_t".to_string(),
+                offset: u32::MAX,
+                instance: true,
+                readonly: false,
+                const_expr: false,
+                value: None,
+                brief_comment: Some("Opaque storage of the type".to_string()),
+                is_private: true,
+            })
+            .into(),
+        );
+
+        if is_value_type {
+            // cannot fail, size_info exists
+            let _ = self.create_valuetype_field_wrapper();
+        }
+        let _ = self.create_size_assert();
     }
 
     // fn make_generic_constraints(
@@ -407,6 +470,7 @@ pub trait CSType: Sized {
         &mut self,
         metadata: &Metadata,
         ctx_collection: &CppContextCollection,
+        config: &GenerationConfig,
         tdi: TypeDefinitionIndex,
     ) {
         let cpp_type = self.get_mut_cpp_type();
@@ -447,6 +511,7 @@ pub trait CSType: Sized {
                     cpp_type,
                     ctx_collection,
                     metadata,
+                    config,
                     &mut template_args,
                 )
             })
@@ -524,17 +589,18 @@ pub trait CSType: Sized {
 
             match full_name.as_str() {
                 "System.Enum" => {
-                    cpp_type.requirements.needs_enum_include();
-                    ENUM_PTR_TYPE.into()
+                    cpp_type.requirements.needs_enum_include(&config.runtime);
+                    config.runtime.enum_ptr_type.clone()
                 }
                 "System.ValueType" => {
-                    cpp_type.requirements.needs_value_include();
-                    VT_PTR_TYPE.into()
+                    cpp_type.requirements.needs_value_include(&config.runtime);
+                    config.runtime.vt_ptr_type.clone()
                 }
                 _ => cpp_type
                     .cppify_name_il2cpp(
                         ctx_collection,
                         metadata,
+                        config,
                         param_type,
                         0,
                         TypeUsage::Parameter,
@@ -557,7 +623,7 @@ pub trait CSType: Sized {
                 true => make_param_cpp_type_name(cpp_type),
             };
 
-            cpp_type.il2cpp_byref(fixup_name, param_type)
+            cpp_type.il2cpp_byref(fixup_name, param_type, config)
         };
 
         CppParam {
@@ -603,13 +669,13 @@ pub trait CSType: Sized {
         ctx_collection: &CppContextCollection,
         config: &GenerationConfig,
         tdi: TypeDefinitionIndex,
-    ) {
+    ) -> Result<()> {
         let cpp_type = self.get_mut_cpp_type();
         let t = Self::get_type_definition(metadata, tdi);
 
         // if no fields, skip
         if t.field_count == 0 {
-            return;
+            return Ok(());
         }
 
         let field_offsets = &metadata
@@ -774,7 +840,8 @@ pub trait CSType: Sized {
                 // Var types are default pointers so we need to get the name component's pointer bool
                 let (field_ty_cpp_name, field_is_pointer) =
                     if f_type.is_constant() && f_type.ty == Il2CppTypeEnum::String {
-                        ("::ConstString".to_string(), false)
+                        cpp_type.requirements.needs_string_view_include();
+                        ("::std::u16string_view".to_string(), false)
                     } else {
                         let include_depth = match f_type.valuetype {
                             true => usize::MAX,
@@ -784,6 +851,7 @@ pub trait CSType: Sized {
                         let field_name_components = cpp_type.cppify_name_il2cpp(
                             ctx_collection,
                             metadata,
+                            config,
                             f_type,
                             include_depth,
                             TypeUsage::FieldName
@@ -800,26 +868,47 @@ pub trait CSType: Sized {
 
                 assert!(def_value.is_none() || (def_value.is_some() && f_type.is_param_optional()));
 
+                // decimal constants are static readonly fields, which are constants for us
+                let decimal_value = match f_type.is_static() && f_type.is_init_only() {
+                    true => Self::decimal_constant_value(
+                        metadata,
+                        tdi,
+                        field,
+                        f_type,
+                        &field_ty_cpp_name,
+                    ),
+                    false => None,
+                };
+                let is_constant = f_type.is_constant() || decimal_value.is_some();
+                let def_value = def_value.or(decimal_value);
+
                 let cpp_field_decl = CppFieldDecl {
                     cpp_name: f_cpp_name,
                     field_ty: field_ty_cpp_name,
                     offset: f_offset.unwrap_or(u32::MAX),
-                    instance: !f_type.is_static() && !f_type.is_constant(),
-                    readonly: f_type.is_constant(),
+                    instance: !f_type.is_static() && !is_constant,
+                    readonly: is_constant,
                     brief_comment: Some(format!("Field {f_name}, offset: 0x{:x}, size: 0x{f_size:x}, def value: {def_value:?}", f_offset.unwrap_or(u32::MAX))),
                     value: def_value,
                     const_expr: false,
                     is_private: false,
                 };
 
+                // the runtime offset table also has the offsets of statics into the class static data
+                let static_offset = match f_type.is_static() && !is_constant {
+                    true => field_offsets.get(i).copied(),
+                    false => None,
+                };
+
                 Some(FieldInfo {
                     cpp_field: cpp_field_decl,
                     field,
                     field_type: f_type,
-                    is_constant: f_type.is_constant(),
+                    is_constant,
                     is_static: f_type.is_static(),
                     is_pointer: field_is_pointer,
                     offset: f_offset,
+                    static_offset,
                     size: f_size,
                 })
             })
@@ -831,7 +920,7 @@ pub trait CSType: Sized {
             // only push def dependency if valuetype field & not a primitive builtin
             if f_type.valuetype && !f_type.ty.is_primitive_builtin() {
                 let field_cpp_tag: CppTypeTag =
-                    CppTypeTag::from_type_data(f_type.data, metadata.metadata);
+                    CppTypeTag::from_type_data(f_type.data, metadata.metadata)?;
                 let field_cpp_td_tag: CppTypeTag = field_cpp_tag.get_tdi().into();
                 let field_cpp_type = ctx_collection.get_cpp_type(field_cpp_td_tag);
 
@@ -853,22 +942,31 @@ pub trait CSType: Sized {
             }
         }
 
+        cpp_type.instance_field_offsets = fields
+            .iter()
+            .filter(|f| !f.is_static && !f.is_constant)
+            .filter_map(|f| Some((f.field.name(metadata.metadata).to_string(), f.offset?)))
+            .collect_vec();
+
         if t.is_value_type() || t.is_enum_type() {
             handle_valuetype_fields(cpp_type, &fields, metadata, tdi);
         } else {
             handle_referencetype_fields(cpp_type, &fields, metadata, tdi);
         }
 
-        handle_static_fields(cpp_type, &fields, metadata, tdi);
+        handle_static_fields(cpp_type, &fields, metadata, config, tdi);
         handle_const_fields(cpp_type, &fields, ctx_collection, metadata, tdi);
+
+        Ok(())
     }
 
     fn make_parents(
         &mut self,
         metadata: &Metadata,
         ctx_collection: &CppContextCollection,
+        config: &GenerationConfig,
         tdi: TypeDefinitionIndex,
-    ) {
+    ) -> Result<()> {
         let cpp_type = self.get_mut_cpp_type();
         let t = &metadata.metadata.global_metadata.type_definitions[tdi];
 
@@ -886,16 +984,17 @@ pub trait CSType: Sized {
                     info!("Skipping type: {ns}::{name} because it has parent index: {} and is not an interface!", t.parent_index);
                 }
             }
-            return;
+            return Ok(());
         }
 
         let parent_type = metadata
             .metadata_registration
             .types
             .get(t.parent_index as usize)
-            .unwrap_or_else(|| panic!("NO PARENT! But valid index found: {}", t.parent_index));
+            .ok_or_else(|| eyre!("NO PARENT! But valid index found: {}", t.parent_index))?;
 
-        let parent_ty: CppTypeTag = CppTypeTag::from_type_data(parent_type.data, metadata.metadata);
+        let parent_ty: CppTypeTag =
+            CppTypeTag::from_type_data(parent_type.data, metadata.metadata)?;
 
         // handle value types and enum types specially
         match t.is_value_type() || t.is_enum_type() {
@@ -924,12 +1023,15 @@ pub trait CSType: Sized {
                     parent_type.ty,
                     Il2CppTypeEnum::Class | Il2CppTypeEnum::Genericinst | Il2CppTypeEnum::Object
                 );
-                assert!(is_ref_type, "Not a class, object or generic inst!");
+                if !is_ref_type {
+                    bail!("Parent {parent_type:?} is not a class, object or generic inst!");
+                }
 
                 // We have a parent, lets do something with it
                 let inherit_type = cpp_type.cppify_name_il2cpp(
                     ctx_collection,
                     metadata,
+                    config,
                     parent_type,
                     usize::MAX,
                     TypeUsage::TypeName,
@@ -940,22 +1042,20 @@ pub trait CSType: Sized {
                     let parent_tdi: TypeDefinitionIndex = parent_ty.into();
 
                     let base_type_context = ctx_collection
-                                    .get_context(parent_ty)
-                                    .or_else(|| ctx_collection.get_context(parent_tdi.into()))
-                                    .unwrap_or_else(|| {
-                                        panic!(
-                                        "No CppContext for base type {inherit_type:?}. Using tag {parent_ty:?}"
-                                    )
-                                    });
+                        .get_context(parent_ty)
+                        .or_else(|| ctx_collection.get_context(parent_tdi.into()))
+                        .ok_or_else(|| {
+                            eyre!(
+                                "No CppContext for base type {inherit_type:?}. Using tag {parent_ty:?}"
+                            )
+                        })?;
 
                     let base_type_cpp_type = ctx_collection
-                                        .get_cpp_type(parent_ty)
-                                        .or_else(|| ctx_collection.get_cpp_type(parent_tdi.into()))
-                                        .unwrap_or_else(|| {
-                                panic!(
-                                    "No CppType for base type {inherit_type:?}. Using tag {parent_ty:?}"
-                                )
-                            });
+                        .get_cpp_type(parent_ty)
+                        .or_else(|| ctx_collection.get_cpp_type(parent_tdi.into()))
+                        .ok_or_else(|| {
+                            eyre!("No CppType for base type {inherit_type:?}. Using tag {parent_ty:?}")
+                        })?;
 
                     cpp_type.requirements.add_impl_include(
                         Some(base_type_cpp_type),
@@ -968,6 +1068,8 @@ pub trait CSType: Sized {
                     .push(inherit_type.remove_pointer().combine_all());
             }
         }
+
+        Ok(())
     }
 
     fn make_interfaces(
@@ -987,6 +1089,7 @@ pub trait CSType: Sized {
             let interface_name_il2cpp = &cpp_type.cppify_name_il2cpp(
                 ctx_collection,
                 metadata,
+                config,
                 int_ty,
                 0,
                 TypeUsage::TypeName,
@@ -994,6 +1097,10 @@ pub trait CSType: Sized {
             let interface_cpp_name = interface_name_il2cpp.remove_pointer().combine_all();
             let interface_cpp_pointer = interface_name_il2cpp.as_pointer().combine_all();
 
+            cpp_type
+                .interfaces
+                .push(interface_name_il2cpp.remove_pointer());
+
             let operator_method_decl = CppMethodDecl {
                 body: Default::default(),
                 brief: Some(format!("Convert operator to {interface_cpp_name:?}")),
@@ -1031,7 +1138,10 @@ pub trait CSType: Sized {
             let convert_line = match t.is_value_type() || t.is_enum_type() {
                 true => {
                     // box
-                    "static_cast<void*>(::il2cpp_utils::Box(this))".to_string()
+                    format!(
+                        "static_cast<void*>({}::Box(this))",
+                        config.runtime.utils_namespace
+                    )
                 }
                 false => "static_cast<void*>(this)".to_string(),
             };
@@ -1191,7 +1301,14 @@ pub trait CSType: Sized {
                 .unwrap();
 
             let p_ty_cpp_name = cpp_type
-                .cppify_name_il2cpp(ctx_collection, metadata, p_type, 0, TypeUsage::PropertyName)
+                .cppify_name_il2cpp(
+                    ctx_collection,
+                    metadata,
+                    config,
+                    p_type,
+                    0,
+                    TypeUsage::PropertyName,
+                )
                 .combine_all();
 
             let _method_map = |p: MethodIndex| {
@@ -1224,19 +1341,21 @@ pub trait CSType: Sized {
         }
     }
 
-    fn create_size_assert(&mut self) {
+    fn create_size_assert(&mut self) -> Result<()> {
         let cpp_type = self.get_mut_cpp_type();
 
         // FIXME: make this work with templated types that either: have a full template (complete instantiation), or only require a pointer (size should be stable)
         // for now, skip templated types
         if cpp_type.cpp_template.is_some() {
-            return;
+            return Ok(());
         }
 
         if let Some(size) = cpp_type.size_info.as_ref().map(|s| s.instance_size) {
             let cpp_name = cpp_type.cpp_name_components.remove_pointer().combine_all();
 
-            assert!(!cpp_name.trim().is_empty(), "CPP Name cannot be empty!");
+            if cpp_name.trim().is_empty() {
+                bail!("CPP Name cannot be empty!");
+            }
 
             let assert = CppStaticAssert {
                 condition: format!("::cordl_internals::size_check_v<{cpp_name}, 0x{size:x}>"),
@@ -1246,10 +1365,129 @@ pub trait CSType: Sized {
             cpp_type
                 .nonmember_declarations
                 .push(Rc::new(CppNonMember::CppStaticAssert(assert)));
+
+            Ok(())
         } else {
-            todo!("Why does this type not have a valid size??? {cpp_type:?}");
+            bail!(
+                "Type {} does not have a valid size",
+                cpp_type.cpp_name_components.combine_all()
+            );
+        }
+    }
+    ///
+    /// Delegates get a factory building a new delegate from a C++ callable,
+    /// and a call operator forwarding to their `Invoke` method
+    ///
+    fn create_delegate_helpers(
+        &mut self,
+        metadata: &Metadata,
+        config: &GenerationConfig,
+        tdi: TypeDefinitionIndex,
+    ) {
+        let cpp_type = self.get_mut_cpp_type();
+        let t = Self::get_type_definition(metadata, tdi);
+
+        if t.parent_index == u32::MAX {
+            return;
         }
+        let parent_ty = &metadata.metadata_registration.types[t.parent_index as usize];
+        let TypeData::TypeDefinitionIndex(parent_tdi) = parent_ty.data else {
+            return;
+        };
+        let parent_td = &metadata.metadata.global_metadata.type_definitions[parent_tdi];
+        if parent_td.full_name(metadata.metadata, false) != "System.MulticastDelegate" {
+            return;
+        }
+
+        let Some(invoke) = cpp_type.declarations.iter().find_map(|d| match d.as_ref() {
+            CppMember::MethodDecl(m) if m.cpp_name == "Invoke" && m.instance => Some(m.clone()),
+            _ => None,
+        }) else {
+            warn!(
+                "Delegate {} has no Invoke method",
+                cpp_type.cpp_name_components.combine_all()
+            );
+            return;
+        };
+
+        cpp_type
+            .requirements
+            .add_def_include(None, CppInclude::new_system("functional"));
+
+        let self_cpp_pointer = cpp_type.cpp_name_components.combine_all();
+        let signature = format!(
+            "{}({})",
+            invoke.return_type,
+            CppParam::params_types(&invoke.parameters).join(", ")
+        );
+        let utils_namespace = &config.runtime.utils_namespace;
+        let type_check = config.runtime.type_check_namespace();
+
+        // templated on the callable, so it has to be defined in the declaration
+        let factory_decl = CppMethodDecl {
+            cpp_name: "New_callback".to_string(),
+            return_type: self_cpp_pointer.clone(),
+            parameters: vec![CppParam {
+                name: "callback".to_string(),
+                ty: "TCallback&&".to_string(),
+                modifiers: "".to_string(),
+                def_value: None,
+            }],
+            instance: false,
+            template: Some(CppTemplate::make_typenames(std::iter::once(
+                "TCallback".to_string(),
+            ))),
+            body: Some(vec![Arc::new(CppLine::make(format!(
+                "return {utils_namespace}::MakeDelegate<{self_cpp_pointer}>(
+                    {type_check}::il2cpp_no_arg_class<{self_cpp_pointer}>::get(),
+                    ::std::function<{signature}>(::std::forward<TCallback>(callback))
+                );"
+            )))]),
+            brief: Some(format!(
+                "Creates a delegate calling `callback`, a {signature} callable"
+            )),
+            is_const: false,
+            is_constexpr: false,
+            is_no_except: false,
+            is_virtual: false,
+            is_implicit_operator: false,
+            is_explicit_operator: false,
+            is_inline: true,
+            prefix_modifiers: vec![],
+            suffix_modifiers: vec![],
+        };
+
+        let call_operator_decl = CppMethodDecl {
+            cpp_name: "operator()".to_string(),
+            brief: Some("Forwards to Invoke".to_string()),
+            ..invoke.clone()
+        };
+        let call_operator_impl = CppMethodImpl {
+            body: vec![Arc::new(CppLine::make(format!(
+                "return this->Invoke({});",
+                CppParam::params_names(&invoke.parameters).join(", ")
+            )))],
+            declaring_cpp_full_name: cpp_type.cpp_name_components.remove_pointer().combine_all(),
+            declaring_type_template: cpp_type
+                .cpp_template
+                .as_ref()
+                .is_some_and(|t| !t.names.is_empty())
+                .then(|| cpp_type.cpp_template.clone())
+                .flatten(),
+            ..call_operator_decl.clone().into()
+        };
+
+        cpp_type
+            .declarations
+            .push(CppMember::MethodDecl(factory_decl).into());
+        cpp_type
+            .declarations
+            .push(CppMember::MethodDecl(call_operator_decl).into());
+        cpp_type
+            .implementations
+            .push(CppMember::MethodImpl(call_operator_impl).into());
     }
+
     ///
     /// add missing size for type
     ///
@@ -1345,7 +1583,7 @@ pub trait CSType: Sized {
         );
     }
 
-    fn create_ref_size(&mut self) {
+    fn create_ref_size(&mut self) -> Result<()> {
         let cpp_type = self.get_mut_cpp_type();
         if let Some(size) = cpp_type.size_info.as_ref().map(|s| s.instance_size) {
             cpp_type.declarations.push(
@@ -1386,14 +1624,20 @@ pub trait CSType: Sized {
                 })
                 .into(),
             );
+
+            Ok(())
         } else {
-            todo!("Why does this type not have a valid size??? {:?}", cpp_type);
+            bail!(
+                "Type {} does not have a valid size",
+                cpp_type.cpp_name_components.combine_all()
+            );
         }
     }
     fn create_enum_backing_type_constant(
         &mut self,
         metadata: &Metadata,
         ctx_collection: &CppContextCollection,
+        config: &GenerationConfig,
         tdi: TypeDefinitionIndex,
     ) {
         let cpp_type = self.get_mut_cpp_type();
@@ -1407,6 +1651,7 @@ pub trait CSType: Sized {
             .cppify_name_il2cpp(
                 ctx_collection,
                 metadata,
+                config,
                 backing_field_ty,
                 0,
                 TypeUsage::TypeName,
@@ -1428,6 +1673,7 @@ pub trait CSType: Sized {
         &mut self,
         metadata: &Metadata,
         ctx_collection: &CppContextCollection,
+        config: &GenerationConfig,
         tdi: TypeDefinitionIndex,
     ) {
         let cpp_type = self.get_mut_cpp_type();
@@ -1443,6 +1689,7 @@ pub trait CSType: Sized {
             .cppify_name_il2cpp(
                 ctx_collection,
                 metadata,
+                config,
                 backing_field,
                 0,
                 TypeUsage::TypeName,
@@ -1450,7 +1697,7 @@ pub trait CSType: Sized {
             .remove_pointer()
             .combine_all();
 
-        let enum_entries = t
+        let enum_values = t
             .fields(metadata.metadata)
             .iter()
             .enumerate()
@@ -1472,10 +1719,15 @@ pub trait CSType: Sized {
                     let value = Self::field_default_value(metadata, field_index)
                         .expect("Enum without value!");
 
-                    // prepend enum name with __E_ to prevent accidentally creating enum values that are reserved for builtin macros
-                    format!("__E_{f_name} = {value},")
+                    (f_name, value)
                 })
             })
+            .collect_vec();
+
+        // prepend enum name with __E_ to prevent accidentally creating enum values that are reserved for builtin macros
+        let enum_entries = enum_values
+            .iter()
+            .map(|(f_name, value)| format!("__E_{f_name} = {value},"))
             .map(|s| -> CppMember { CppMember::CppLine(s.into()) });
 
         let nested_struct = CppNestedStruct {
@@ -1492,55 +1744,568 @@ pub trait CSType: Sized {
             .declarations
             .push(CppMember::NestedStruct(nested_struct).into());
 
-        let operator_body = format!("return static_cast<{unwrapped_name}>(this->value__);");
-        let unwrapped_operator_decl = CppMethodDecl {
-            cpp_name: Default::default(),
-            instance: true,
-            return_type: unwrapped_name,
+        let operator_body = format!("return static_cast<{unwrapped_name}>(this->value__);");
+        let unwrapped_operator_decl = CppMethodDecl {
+            cpp_name: Default::default(),
+            instance: true,
+            return_type: unwrapped_name,
+
+            brief: Some("Conversion into unwrapped enum value".to_string()),
+            body: Some(vec![Arc::new(CppLine::make(operator_body))]),
+            is_const: true,
+            is_constexpr: true,
+            is_virtual: false,
+            is_explicit_operator: false,
+            is_implicit_operator: true,
+            is_no_except: true,
+            parameters: vec![],
+            prefix_modifiers: vec![],
+            suffix_modifiers: vec![],
+            template: None,
+            is_inline: true,
+        };
+        // convert to proper backing type
+        let backing_operator_body = format!("return static_cast<{enum_base}>(this->value__);");
+        let backing_operator_decl = CppMethodDecl {
+            brief: Some("Conversion into unwrapped enum value".to_string()),
+            return_type: enum_base,
+            body: Some(vec![Arc::new(CppLine::make(backing_operator_body))]),
+            is_explicit_operator: true,
+            ..unwrapped_operator_decl.clone()
+        };
+
+        cpp_type
+            .declarations
+            .push(CppMember::MethodDecl(unwrapped_operator_decl).into());
+        cpp_type
+            .declarations
+            .push(CppMember::MethodDecl(backing_operator_decl.clone()).into());
+
+        Self::create_enum_reflection(cpp_type, &unwrapped_name, &enum_values);
+
+        if Self::is_flags_enum(metadata, tdi) {
+            Self::create_enum_flags_operators(cpp_type, &unwrapped_name, &backing_operator_decl);
+        }
+    }
+
+    ///
+    /// Name table of the enum values, with `to_string`/`from_string` looking values up in it.
+    /// The table is also what cordl_internals uses to format enums
+    ///
+    fn create_enum_reflection(
+        cpp_type: &mut CppType,
+        unwrapped_name: &str,
+        enum_values: &[(&str, String)],
+    ) {
+        let entries_name = "__CORDL_ENUM_ENTRIES";
+        let entries = enum_values
+            .iter()
+            .map(|(f_name, _)| {
+                format!(
+                    "{{\"{}\", {unwrapped_name}::__E_{f_name}}}",
+                    escape_cpp_literal(f_name)
+                )
+            })
+            .join(", ");
+
+        let entries_decl = CppFieldDecl {
+            cpp_name: entries_name.to_string(),
+            field_ty: format!(
+                "::cordl_internals::EnumEntries<{unwrapped_name}, {}>",
+                enum_values.len()
+            ),
+            offset: u32::MAX,
+            instance: false,
+            readonly: true,
+            const_expr: true,
+            value: Some(format!("{{{entries}}}")),
+            brief_comment: Some("Names of the enum values".to_string()),
+            is_private: false,
+        };
+
+        let to_string_decl = CppMethodDecl {
+            cpp_name: "to_string".to_string(),
+            return_type: "::std::string_view".to_string(),
+            parameters: vec![],
+            instance: true,
+            template: None,
+            suffix_modifiers: vec![],
+            prefix_modifiers: vec![],
+            is_virtual: false,
+            is_constexpr: true,
+            is_const: true,
+            is_no_except: true,
+            is_implicit_operator: false,
+            is_explicit_operator: false,
+            is_inline: true,
+            brief: Some("Name of the value, empty if it has none".to_string()),
+            body: Some(vec![Arc::new(CppLine::make(format!(
+                "return ::cordl_internals::enum_name({entries_name}, static_cast<{unwrapped_name}>(*this));"
+            )))]),
+        };
+
+        let from_string_decl = CppMethodDecl {
+            cpp_name: "from_string".to_string(),
+            return_type: format!("::std::optional<{unwrapped_name}>"),
+            parameters: vec![CppParam {
+                name: "name".to_string(),
+                ty: "::std::string_view".to_string(),
+                modifiers: "".to_string(),
+                def_value: None,
+            }],
+            instance: false,
+            is_const: false,
+            brief: Some("Value with the given name".to_string()),
+            body: Some(vec![Arc::new(CppLine::make(format!(
+                "return ::cordl_internals::enum_value({entries_name}, name);"
+            )))]),
+            ..to_string_decl.clone()
+        };
+
+        cpp_type
+            .declarations
+            .push(CppMember::FieldDecl(entries_decl).into());
+        cpp_type
+            .declarations
+            .push(CppMember::MethodDecl(to_string_decl).into());
+        cpp_type
+            .declarations
+            .push(CppMember::MethodDecl(from_string_decl).into());
+    }
+
+    ///
+    /// Whether the enum is `[System.Flags]`.
+    /// Only if its custom attribute data can't be read, an enum is treated as flags
+    /// if it has at least three single bit values and every other value is a combination of those.
+    /// Enums counting up from 0 or 1 (e.g. 1, 2, 3) are sequential, not flags.
+    ///
+    fn is_flags_enum(metadata: &Metadata, tdi: TypeDefinitionIndex) -> bool {
+        if let Some(has_attribute) = Self::has_flags_attribute(metadata, tdi) {
+            return has_attribute;
+        }
+
+        let t = Self::get_type_definition(metadata, tdi);
+
+        let values = t
+            .fields(metadata.metadata)
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| {
+                metadata.metadata_registration.types[field.type_index as usize].is_static()
+            })
+            .filter_map(|(i, _)| {
+                let field_index = FieldIndex::new(t.field_start.index() + i as u32);
+                Self::field_default_integer(metadata, field_index)
+            })
+            .filter(|v| *v != 0)
+            .sorted()
+            .dedup()
+            .collect_vec();
+
+        let single_bits = values
+            .iter()
+            .filter(|v| v.count_ones() == 1)
+            .fold(0u64, |acc, v| acc | v);
+
+        // -1 is commonly used for "all" flags
+        let is_combination = |v: &u64| v & !single_bits == 0 || *v == u64::MAX;
+
+        let is_sequential = values.len() >= 3
+            && values.first() == Some(&1)
+            && values.windows(2).all(|w| w[1] == w[0] + 1);
+
+        single_bits.count_ones() >= 3 && values.iter().all(is_combination) && !is_sequential
+    }
+
+    ///
+    /// Looks for `System.FlagsAttribute` in the type's custom attributes.
+    /// None if the type's image or attribute data can't be found
+    ///
+    fn has_flags_attribute(metadata: &Metadata, tdi: TypeDefinitionIndex) -> Option<bool> {
+        let t = Self::get_type_definition(metadata, tdi);
+        let (ctors, _) = Self::custom_attributes(metadata, tdi, t.token)?;
+
+        let is_flags = ctors
+            .into_iter()
+            .any(|ctor| Self::is_attribute_ctor(metadata, ctor, "System", "FlagsAttribute"));
+
+        Some(is_flags)
+    }
+
+    ///
+    /// The custom attribute data of the type or member with `token`, declared in the image of `tdi`.
+    /// It starts with the attribute count followed by the index of each attribute's constructor,
+    /// returned with a cursor at the serialized arguments of each attribute that follow.
+    /// None if the image or attribute data can't be found
+    ///
+    fn custom_attributes<'a>(
+        metadata: &'a Metadata,
+        tdi: TypeDefinitionIndex,
+        token: u32,
+    ) -> Option<(Vec<u32>, Cursor<&'a [u8]>)> {
+        let global_metadata = &metadata.metadata.global_metadata;
+
+        // attribute tokens are only unique within their image
+        let image = global_metadata.images.as_vec().iter().find(|image| {
+            let start = image.type_start.index();
+            (start..start + image.type_count).contains(&tdi.index())
+        })?;
+
+        let ranges_start = image.custom_attribute_start as usize;
+        let ranges_end = ranges_start + image.custom_attribute_count as usize;
+        let Some(range) = global_metadata
+            .attribute_data_range
+            .as_vec()
+            .get(ranges_start..ranges_end)?
+            .iter()
+            .find(|range| range.token == token)
+        else {
+            // no custom attributes at all
+            return Some((vec![], Cursor::new(&[][..])));
+        };
+
+        let data = global_metadata
+            .attribute_data
+            .as_vec()
+            .get(range.start_offset as usize..)?;
+        let mut cursor = Cursor::new(data);
+
+        let count = cursor.read_compressed_u32::<Endian>().ok()?;
+        let ctors: Vec<u32> = (0..count)
+            .map(|_| cursor.read_u32::<Endian>().ok())
+            .collect::<Option<_>>()?;
+
+        Some((ctors, cursor))
+    }
+
+    fn is_attribute_ctor(metadata: &Metadata, ctor: u32, namespace: &str, name: &str) -> bool {
+        let global_metadata = &metadata.metadata.global_metadata;
+
+        global_metadata
+            .methods
+            .as_vec()
+            .get(ctor as usize)
+            .is_some_and(|ctor| {
+                let attribute_td = &global_metadata.type_definitions[ctor.declaring_type];
+
+                attribute_td.namespace(metadata.metadata) == namespace
+                    && attribute_td.name(metadata.metadata) == name
+            })
+    }
+
+    ///
+    /// Reads the serialized arguments of the next attribute,
+    /// which are the constructor arguments followed by the named field and property arguments.
+    /// Only the constructor arguments are returned, see [`Self::read_attribute_value`]
+    ///
+    fn read_attribute_args(metadata: &Metadata, cursor: &mut Cursor<&[u8]>) -> Option<Vec<u64>> {
+        let arg_count = cursor.read_compressed_u32::<Endian>().ok()?;
+        let field_count = cursor.read_compressed_u32::<Endian>().ok()?;
+        let property_count = cursor.read_compressed_u32::<Endian>().ok()?;
+
+        let args = (0..arg_count)
+            .map(|_| Self::read_attribute_value(metadata, cursor))
+            .collect::<Option<Vec<_>>>()?;
+
+        for _ in 0..field_count + property_count {
+            Self::read_attribute_value(metadata, cursor)?;
+
+            // negative member indices are members of a base type, followed by the type's index
+            let member_index = cursor.read_compressed_i32::<Endian>().ok()?;
+            if member_index < 0 {
+                cursor.read_compressed_u32::<Endian>().ok()?;
+            }
+        }
+
+        Some(args)
+    }
+
+    ///
+    /// Reads a serialized attribute argument, which starts with its `Il2CppTypeEnum`.
+    /// Integers are returned as their sign extended bits, strings, types and null objects are skipped and read as 0.
+    /// None for arrays, which no decoded attribute needs
+    ///
+    fn read_attribute_value(metadata: &Metadata, cursor: &mut Cursor<&[u8]>) -> Option<u64> {
+        let ty = match cursor.read_u8().ok()? {
+            0x02 => Il2CppTypeEnum::Boolean,
+            0x03 => Il2CppTypeEnum::Char,
+            0x04 => Il2CppTypeEnum::I1,
+            0x05 => Il2CppTypeEnum::U1,
+            0x06 => Il2CppTypeEnum::I2,
+            0x07 => Il2CppTypeEnum::U2,
+            0x08 => Il2CppTypeEnum::I4,
+            0x09 => Il2CppTypeEnum::U4,
+            0x0a => Il2CppTypeEnum::I8,
+            0x0b => Il2CppTypeEnum::U8,
+            0x0c => Il2CppTypeEnum::R4,
+            0x0d => Il2CppTypeEnum::R8,
+            0x0e => Il2CppTypeEnum::String,
+            0x12 => Il2CppTypeEnum::Class,
+            0x1c => Il2CppTypeEnum::Object,
+            // IL2CPP_TYPE_ENUM, the enum's type index is followed by a value of its underlying type
+            0x55 => {
+                let type_index = cursor.read_compressed_i32::<Endian>().ok()?;
+                let enum_ty = metadata
+                    .metadata_registration
+                    .types
+                    .get(type_index as usize)?;
+                let enum_td = Self::get_type_definition(metadata, metadata.type_tdi(enum_ty)?);
+
+                metadata
+                    .metadata_registration
+                    .types
+                    .get(enum_td.element_type_index as usize)?
+                    .ty
+            }
+            // IL2CPP_TYPE_IL2CPP_TYPE_INDEX, a `System.Type` argument
+            0xff => {
+                cursor.read_compressed_i32::<Endian>().ok()?;
+                return Some(0);
+            }
+            _ => return None,
+        };
+
+        let value = match ty {
+            Il2CppTypeEnum::Boolean | Il2CppTypeEnum::U1 => cursor.read_u8().ok()? as u64,
+            Il2CppTypeEnum::I1 => cursor.read_i8().ok()? as u64,
+            Il2CppTypeEnum::Char | Il2CppTypeEnum::U2 => cursor.read_u16::<Endian>().ok()? as u64,
+            Il2CppTypeEnum::I2 => cursor.read_i16::<Endian>().ok()? as u64,
+            Il2CppTypeEnum::I4 => cursor.read_compressed_i32::<Endian>().ok()? as u64,
+            Il2CppTypeEnum::U4 => cursor.read_compressed_u32::<Endian>().ok()? as u64,
+            Il2CppTypeEnum::R4 => cursor.read_u32::<Endian>().ok()? as u64,
+            Il2CppTypeEnum::I8 | Il2CppTypeEnum::U8 | Il2CppTypeEnum::R8 => {
+                cursor.read_u64::<Endian>().ok()?
+            }
+            Il2CppTypeEnum::String => {
+                // -1 for null strings
+                let len = cursor.read_compressed_i32::<Endian>().ok()?;
+                cursor.set_position(cursor.position() + len.max(0) as u64);
+                0
+            }
+            // null
+            Il2CppTypeEnum::Class | Il2CppTypeEnum::Object => 0,
+            _ => return None,
+        };
+
+        Some(value)
+    }
+
+    ///
+    /// C# compilers emit `const decimal` fields as `static readonly` fields with a
+    /// `System.Runtime.CompilerServices.DecimalConstantAttribute(scale, sign, hi, mid, lo)`.
+    /// Returns the value as a `System.Decimal` constructor call on `decimal_cpp_name`,
+    /// None if the field has no such attribute or `System.Decimal` has unknown fields
+    ///
+    fn decimal_constant_value(
+        metadata: &Metadata,
+        tdi: TypeDefinitionIndex,
+        field: &Il2CppFieldDefinition,
+        field_type: &Il2CppType,
+        decimal_cpp_name: &str,
+    ) -> Option<String> {
+        let decimal_tdi = match field_type.data {
+            TypeData::TypeDefinitionIndex(decimal_tdi) => decimal_tdi,
+            _ => return None,
+        };
+        let decimal_td = Self::get_type_definition(metadata, decimal_tdi);
+        if field_type.ty != Il2CppTypeEnum::Valuetype
+            || decimal_td.namespace(metadata.metadata) != "System"
+            || decimal_td.name(metadata.metadata) != "Decimal"
+        {
+            return None;
+        }
+
+        let (ctors, mut cursor) = Self::custom_attributes(metadata, tdi, field.token)?;
+
+        // the arguments of every attribute before it have to be read to reach its own
+        let mut args = None;
+        for ctor in ctors {
+            let ctor_args = Self::read_attribute_args(metadata, &mut cursor)?;
+
+            if Self::is_attribute_ctor(
+                metadata,
+                ctor,
+                "System.Runtime.CompilerServices",
+                "DecimalConstantAttribute",
+            ) {
+                args = Some(ctor_args);
+                break;
+            }
+        }
+
+        let args = args?;
+        let [scale, sign, hi, mid, lo] = args[..] else {
+            return None;
+        };
+        let (hi, mid, lo) = (hi as u32, mid as u32, lo as u32);
+
+        // the scale is in bits 16-23 of flags, the sign in bit 31
+        let sign_bit = if sign as u8 != 0 { 0x8000_0000 } else { 0 };
+        let flags = ((scale as u8 as u32) << 16) | sign_bit;
+
+        // mono's decimal has flags, hi, lo and mid, the .NET Core one flags, hi32 and lo64
+        let ctor_args = decimal_td
+            .fields(metadata.metadata)
+            .iter()
+            .filter(|f| {
+                let f_type = &metadata.metadata_registration.types[f.type_index as usize];
+                !f_type.is_static() && !f_type.is_constant()
+            })
+            .map(|f| match f.name(metadata.metadata) {
+                "flags" | "_flags" => Some(format!("static_cast<int32_t>(0x{flags:x})")),
+                "hi" | "_hi32" => Some(format!("static_cast<int32_t>(0x{hi:x})")),
+                "mid" => Some(format!("static_cast<int32_t>(0x{mid:x})")),
+                "lo" => Some(format!("static_cast<int32_t>(0x{lo:x})")),
+                "_lo64" => Some(format!(
+                    "static_cast<uint64_t>(0x{:x}u)",
+                    ((mid as u64) << 32) | lo as u64
+                )),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(format!("{decimal_cpp_name}({})", ctor_args.join(", ")))
+    }
+
+    ///
+    /// Bitwise operators and `has_flag` for flag enums.
+    /// The unwrapped enum gets friend operators, found through ADL since it is nested in the wrapper.
+    /// The wrapper converts into the unwrapped enum, so it only needs the compound assignments and `has_flag`
+    ///
+    fn create_enum_flags_operators(
+        cpp_type: &mut CppType,
+        unwrapped_name: &str,
+        backing_operator_decl: &CppMethodDecl,
+    ) {
+        let enum_base = &backing_operator_decl.return_type;
+        let cast = |v: &str| format!("static_cast<{enum_base}>({v})");
+        let param = |name: &str, ty: String| CppParam {
+            name: name.to_string(),
+            ty,
+            modifiers: "".to_string(),
+            def_value: None,
+        };
+
+        let friend_decl = CppMethodDecl {
+            prefix_modifiers: vec!["friend".to_string()],
+            is_const: false,
+            is_implicit_operator: false,
+            is_explicit_operator: false,
+            ..backing_operator_decl.clone()
+        };
+
+        let mut operators = ["|", "&", "^"]
+            .into_iter()
+            .flat_map(|op| {
+                let binary = CppMethodDecl {
+                    cpp_name: format!("operator{op}"),
+                    return_type: unwrapped_name.to_string(),
+                    parameters: vec![
+                        param("a", unwrapped_name.to_string()),
+                        param("b", unwrapped_name.to_string()),
+                    ],
+                    brief: Some(format!("Flags operator{op}")),
+                    body: Some(vec![Arc::new(CppLine::make(format!(
+                        "return static_cast<{unwrapped_name}>({}({} {op} {}));",
+                        enum_base,
+                        cast("a"),
+                        cast("b")
+                    )))]),
+                    ..friend_decl.clone()
+                };
+                let assign = CppMethodDecl {
+                    cpp_name: format!("operator{op}="),
+                    return_type: format!("{unwrapped_name}&"),
+                    parameters: vec![
+                        param("a", format!("{unwrapped_name}&")),
+                        param("b", unwrapped_name.to_string()),
+                    ],
+                    brief: Some(format!("Flags operator{op}=")),
+                    body: Some(vec![Arc::new(CppLine::make(format!(
+                        "return a = a {op} b;"
+                    )))]),
+                    ..friend_decl.clone()
+                };
+                let wrapper_assign = CppMethodDecl {
+                    cpp_name: format!("operator{op}="),
+                    return_type: format!("{}&", cpp_type.cpp_name()),
+                    parameters: vec![param("other", unwrapped_name.to_string())],
+                    instance: true,
+                    is_const: false,
+                    is_implicit_operator: false,
+                    is_explicit_operator: false,
+                    brief: Some(format!("Flags operator{op}=")),
+                    body: Some(vec![
+                        Arc::new(CppLine::make(format!(
+                            "this->value__ = {};",
+                            cast(&format!("this->value__ {op} {}", cast("other")))
+                        ))),
+                        Arc::new(CppLine::make("return *this;".to_string())),
+                    ]),
+                    ..backing_operator_decl.clone()
+                };
+
+                [binary, assign, wrapper_assign]
+            })
+            .collect_vec();
 
-            brief: Some("Conversion into unwrapped enum value".to_string()),
-            body: Some(vec![Arc::new(CppLine::make(operator_body))]),
+        operators.push(CppMethodDecl {
+            cpp_name: "operator~".to_string(),
+            return_type: unwrapped_name.to_string(),
+            parameters: vec![param("a", unwrapped_name.to_string())],
+            brief: Some("Flags operator~".to_string()),
+            body: Some(vec![Arc::new(CppLine::make(format!(
+                "return static_cast<{unwrapped_name}>({}(~{}));",
+                enum_base,
+                cast("a")
+            )))]),
+            ..friend_decl.clone()
+        });
+        operators.push(CppMethodDecl {
+            cpp_name: "has_flag".to_string(),
+            return_type: "bool".to_string(),
+            parameters: vec![
+                param("value", unwrapped_name.to_string()),
+                param("flag", unwrapped_name.to_string()),
+            ],
+            brief: Some("Whether all bits of flag are set in value".to_string()),
+            body: Some(vec![Arc::new(CppLine::make(
+                "return (value & flag) == flag;".to_string(),
+            ))]),
+            ..friend_decl
+        });
+        operators.push(CppMethodDecl {
+            cpp_name: "has_flag".to_string(),
+            return_type: "bool".to_string(),
+            parameters: vec![param("flag", unwrapped_name.to_string())],
+            instance: true,
             is_const: true,
-            is_constexpr: true,
-            is_virtual: false,
+            is_implicit_operator: false,
             is_explicit_operator: false,
-            is_implicit_operator: true,
-            is_no_except: true,
-            parameters: vec![],
-            prefix_modifiers: vec![],
-            suffix_modifiers: vec![],
-            template: None,
-            is_inline: true,
-        };
-        // convert to proper backing type
-        let backing_operator_body = format!("return static_cast<{enum_base}>(this->value__);");
-        let backing_operator_decl = CppMethodDecl {
-            brief: Some("Conversion into unwrapped enum value".to_string()),
-            return_type: enum_base,
-            body: Some(vec![Arc::new(CppLine::make(backing_operator_body))]),
-            is_explicit_operator: true,
-            ..unwrapped_operator_decl.clone()
-        };
+            brief: Some("Whether all bits of flag are set".to_string()),
+            body: Some(vec![Arc::new(CppLine::make(format!(
+                "return (this->value__ & {0}) == {0};",
+                cast("flag")
+            )))]),
+            ..backing_operator_decl.clone()
+        });
 
-        cpp_type
-            .declarations
-            .push(CppMember::MethodDecl(unwrapped_operator_decl).into());
-        cpp_type
-            .declarations
-            .push(CppMember::MethodDecl(backing_operator_decl).into());
+        cpp_type.declarations.extend(
+            operators
+                .into_iter()
+                .map(|op| CppMember::MethodDecl(op).into()),
+        );
     }
 
-    fn create_valuetype_field_wrapper(&mut self) {
+    fn create_valuetype_field_wrapper(&mut self) -> Result<()> {
         let cpp_type = self.get_mut_cpp_type();
-        if cpp_type.size_info.is_none() {
-            todo!("Why does this type not have a valid size??? {:?}", cpp_type);
-        }
 
-        let size = cpp_type
-            .size_info
-            .as_ref()
-            .map(|s| s.instance_size)
-            .unwrap();
+        let Some(size) = cpp_type.size_info.as_ref().map(|s| s.instance_size) else {
+            bail!(
+                "Type {} does not have a valid size",
+                cpp_type.cpp_name_components.combine_all()
+            );
+        };
 
         cpp_type.requirements.needs_byte_include();
         cpp_type.declarations.push(
@@ -1586,6 +2351,8 @@ pub trait CSType: Sized {
         //     })
         //     .into(),
         // );
+
+        Ok(())
     }
 
     fn create_valuetype_constructor(
@@ -1615,7 +2382,14 @@ pub trait CSType: Sized {
                 }
 
                 let f_type_cpp_name = cpp_type
-                    .cppify_name_il2cpp(ctx_collection, metadata, f_type, 0, TypeUsage::FieldName)
+                    .cppify_name_il2cpp(
+                        ctx_collection,
+                        metadata,
+                        config,
+                        f_type,
+                        0,
+                        TypeUsage::FieldName,
+                    )
                     .combine_all();
 
                 // Get the inner type of a Generic Inst
@@ -1715,11 +2489,14 @@ pub trait CSType: Sized {
             .push(CppMember::ConstructorImpl(constructor_impl).into());
     }
 
-    fn create_valuetype_default_constructors(&mut self) {
+    fn create_valuetype_default_constructors(&mut self, config: &GenerationConfig) {
         let cpp_type = self.get_mut_cpp_type();
         // create the various copy and move ctors and operators
         let cpp_name = cpp_type.cpp_name();
-        let wrapper = format!("{VALUE_WRAPPER_TYPE}<{VALUE_TYPE_WRAPPER_SIZE}>::instance");
+        let wrapper = format!(
+            "{}<{VALUE_TYPE_WRAPPER_SIZE}>::instance",
+            config.runtime.value_wrapper_type
+        );
 
         let move_ctor = CppConstructorDecl {
             cpp_name: cpp_name.clone(),
@@ -1984,9 +2761,10 @@ pub trait CSType: Sized {
             .into(),
         );
     }
-    fn create_ref_default_operators(&mut self) {
+    fn create_ref_default_operators(&mut self, config: &GenerationConfig) {
         let cpp_type = self.get_mut_cpp_type();
         let cpp_name = cpp_type.cpp_name();
+        let wrapper_instance = &config.runtime.reference_wrapper_instance;
 
         // Skip if System.ValueType or System.Enum
         if cpp_type.namespace() == "System"
@@ -2005,12 +2783,12 @@ pub trait CSType: Sized {
                 line: format!(
                     "
   constexpr {cpp_name}& operator=(std::nullptr_t) noexcept {{
-    this->{REFERENCE_WRAPPER_INSTANCE_NAME} = nullptr;
+    this->{wrapper_instance} = nullptr;
     return *this;
   }};
 
   constexpr {cpp_name}& operator=(void* o) noexcept {{
-    this->{REFERENCE_WRAPPER_INSTANCE_NAME} = o;
+    this->{wrapper_instance} = o;
     return *this;
   }};
 
@@ -2140,6 +2918,52 @@ pub trait CSType: Sized {
             .push(CppMember::FieldDecl(il2cpp_metadata_type_index).into());
     }
 
+    /// `static void EnsureClassInit()`, which initializes the class and runs its static constructor
+    fn add_class_init_method(&mut self, config: &GenerationConfig) {
+        let klass_resolver = self.classof_cpp_name(config);
+        let cpp_type = self.get_mut_cpp_type();
+
+        let class_init_decl = CppMethodDecl {
+            cpp_name: CLASS_INIT_METHOD_NAME.to_string(),
+            return_type: "void".to_string(),
+            parameters: vec![],
+            instance: false,
+            template: None,
+            body: None,
+            brief: Some("Runs the static constructor if it hasn't run yet".to_string()),
+            is_const: false,
+            is_constexpr: false,
+            is_no_except: false,
+            is_virtual: false,
+            is_implicit_operator: false,
+            is_explicit_operator: false,
+            is_inline: true,
+            prefix_modifiers: vec![],
+            suffix_modifiers: vec![],
+        };
+
+        let class_init_impl = CppMethodImpl {
+            body: vec![Arc::new(CppLine::make(format!(
+                "::cordl_internals::EnsureClassInit<{klass_resolver}>();"
+            )))],
+            declaring_cpp_full_name: cpp_type.cpp_name_components.remove_pointer().combine_all(),
+            declaring_type_template: cpp_type
+                .cpp_template
+                .as_ref()
+                .is_some_and(|t| !t.names.is_empty())
+                .then(|| cpp_type.cpp_template.clone())
+                .flatten(),
+            ..class_init_decl.clone().into()
+        };
+
+        cpp_type
+            .declarations
+            .push(CppMember::MethodDecl(class_init_decl).into());
+        cpp_type
+            .implementations
+            .push(CppMember::MethodImpl(class_init_impl).into());
+    }
+
     fn delete_default_ctor(&mut self) {
         let cpp_type = self.get_mut_cpp_type();
         let t = &cpp_type.cpp_name_components.name;
@@ -2173,6 +2997,7 @@ pub trait CSType: Sized {
         declaring_type: &Il2CppTypeDefinition,
         m_params: &[CppParam],
         template: &Option<CppTemplate>,
+        config: &GenerationConfig,
     ) {
         if declaring_type.is_value_type() || declaring_type.is_enum_type() {
             return;
@@ -2213,7 +3038,8 @@ pub trait CSType: Sized {
         let base_ctor_params = CppParam::params_names(&decl.parameters).join(", ");
 
         let allocate_call = format!(
-            "THROW_UNLESS(::il2cpp_utils::NewSpecific<{ty_full_cpp_name}>({base_ctor_params}))"
+            "THROW_UNLESS({}::NewSpecific<{ty_full_cpp_name}>({base_ctor_params}))",
+            config.runtime.utils_namespace
         );
 
         let declaring_template = if cpp_type
@@ -2259,8 +3085,8 @@ pub trait CSType: Sized {
 
         // TODO: sanitize method name for c++
         let m_name = method.name(metadata.metadata);
+        // static constructors are run through EnsureClassInit instead
         if m_name == ".cctor" {
-            // info!("Skipping {}", m_name);
             return;
         }
 
@@ -2333,7 +3159,14 @@ pub trait CSType: Sized {
                 .map(|t| &metadata.metadata_registration.types[*t as usize])
                 .map(|t| {
                     cpp_type
-                        .cppify_name_il2cpp(ctx_collection, metadata, t, 0, TypeUsage::GenericArg)
+                        .cppify_name_il2cpp(
+                            ctx_collection,
+                            metadata,
+                            config,
+                            t,
+                            0,
+                            TypeUsage::GenericArg,
+                        )
                         .combine_all()
                 })
                 .collect_vec()
@@ -2343,16 +3176,17 @@ pub trait CSType: Sized {
         let make_ret_cpp_type_name = |cpp_type: &mut CppType| -> String {
             let full_name = m_ret_type.full_name(metadata.metadata);
             if full_name == "System.Enum" {
-                cpp_type.requirements.needs_enum_include();
-                ENUM_PTR_TYPE.into()
+                cpp_type.requirements.needs_enum_include(&config.runtime);
+                config.runtime.enum_ptr_type.clone()
             } else if full_name == "System.ValueType" {
-                cpp_type.requirements.needs_value_include();
-                VT_PTR_TYPE.into()
+                cpp_type.requirements.needs_value_include(&config.runtime);
+                config.runtime.vt_ptr_type.clone()
             } else {
                 cpp_type
                     .cppify_name_il2cpp(
                         ctx_collection,
                         metadata,
+                        config,
                         m_ret_type,
                         0,
                         TypeUsage::ReturnType,
@@ -2372,12 +3206,18 @@ pub trait CSType: Sized {
                 true => make_ret_cpp_type_name(cpp_type),
             };
 
-            cpp_type.il2cpp_byref(fixup_name, m_ret_type)
+            cpp_type.il2cpp_byref(fixup_name, m_ret_type, config)
         };
 
         // Reference type constructor
         if m_name == ".ctor" {
-            Self::create_ref_constructor(cpp_type, declaring_type, &m_params_with_def, &template);
+            Self::create_ref_constructor(
+                cpp_type,
+                declaring_type,
+                &m_params_with_def,
+                &template,
+                config,
+            );
         }
         let cpp_m_name = {
             let cpp_m_name = config.name_cpp(m_name);
@@ -2447,6 +3287,8 @@ pub trait CSType: Sized {
         };
 
         const METHOD_INFO_VAR_NAME: &str = "___internal_method";
+        let utils_namespace = &config.runtime.utils_namespace;
+        let type_check = config.runtime.type_check_namespace();
 
         let method_invoke_params = vec![instance_ptr.as_str(), METHOD_INFO_VAR_NAME];
         let param_names = CppParam::params_names(&method_decl.parameters).map(|s| s.as_str());
@@ -2454,7 +3296,7 @@ pub trait CSType: Sized {
             cpp_type.cpp_name_components.remove_pointer().combine_all();
 
         let declaring_classof_call = format!(
-            "::il2cpp_utils::il2cpp_type_check::il2cpp_no_arg_class<{}>::get()",
+            "{type_check}::il2cpp_no_arg_class<{}>::get()",
             cpp_type.cpp_name_components.combine_all()
         );
 
@@ -2462,42 +3304,76 @@ pub trait CSType: Sized {
             "il2cpp_functions::object_get_class(reinterpret_cast<Il2CppObject*>(this))";
 
         let params_types_format: String = CppParam::params_types(&method_decl.parameters)
-            .map(|t| format!("::il2cpp_utils::il2cpp_type_check::il2cpp_no_arg_type<{t}>::get()"))
+            .map(|t| format!("{type_check}::il2cpp_no_arg_type<{t}>::get()"))
             .join(", ");
         let params_types_count = method_decl.parameters.len();
 
-        let resolve_instance_slot_lines = if method.slot != u16::MAX {
-            let slot = &method.slot;
-            vec![format!(
-                "auto* {METHOD_INFO_VAR_NAME} = THROW_UNLESS((::il2cpp_utils::ResolveVtableSlot(
+        // virtual methods resolve against the interface or base method that introduced their slot
+        let slot_origin = (method.slot != u16::MAX && !cpp_type.is_interface)
+            .then(|| find_slot_origin(metadata, method.declaring_type, method.slot))
+            .flatten();
+
+        let resolve_instance_slot_lines = match slot_origin {
+            Some(SlotOrigin::Interface {
+                ty,
+                slot,
+                method_index,
+            }) => {
+                let interface_cpp_name = cpp_type
+                    .cppify_name_il2cpp(
+                        ctx_collection,
+                        metadata,
+                        config,
+                        ty,
+                        0,
+                        TypeUsage::TypeName,
+                    )
+                    .combine_all();
+                let implemented = method_index
+                    .map(|m| metadata.metadata.global_metadata.methods[m].name(metadata.metadata))
+                    .unwrap_or("<unknown>");
+
+                vec![
+                    format!("// implements {interface_cpp_name}::{implemented}"),
+                    format!(
+                        "auto* {METHOD_INFO_VAR_NAME} = THROW_UNLESS(({utils_namespace}::ResolveVtableSlot(
                     {extract_self_class},
-                    {declaring_classof_call},
+                    {type_check}::il2cpp_no_arg_class<{interface_cpp_name}>::get(),
                     {slot}
                 )));"
-            )]
-        } else {
-            vec![]
-        };
-
-        // TODO: link the method to the interface that originally declared it
-        // then the resolve should look something like:
-        // resolve(classof(GlobalNamespace::BeatmapLevelPack*), classof(GlobalNamespace::IBeatmapLevelPack*), 0);
-        // that way the resolve should work correctly, but it should only happen like that for non-interfaces
-
-        let _resolve_metadata_slot_lines = if method.slot != u16::MAX {
-            let self_classof_call = "";
-            let declaring_classof_call = "";
-            let slot = &method.slot;
+                    ),
+                ]
+            }
+            origin => {
+                let slot = &method.slot;
+                let overrides = match origin {
+                    Some(SlotOrigin::Base { tdi, method_index }) => {
+                        let base_td = &metadata.metadata.global_metadata.type_definitions[tdi];
+                        let base_method = &metadata.metadata.global_metadata.methods[method_index];
+
+                        Some(format!(
+                            "// overrides {}::{}",
+                            base_td.full_name(metadata.metadata, true),
+                            base_method.name(metadata.metadata)
+                        ))
+                    }
+                    _ => None,
+                };
 
-            vec![format!(
-                "auto* {METHOD_INFO_VAR_NAME} = THROW_UNLESS((::il2cpp_utils::ResolveVtableSlot(
-                    {self_classof_call},
+                // vtable slots are shared with derived types, so our own class works here
+                overrides
+                    .into_iter()
+                    .chain((method.slot != u16::MAX).then(|| {
+                        format!(
+                            "auto* {METHOD_INFO_VAR_NAME} = THROW_UNLESS(({utils_namespace}::ResolveVtableSlot(
+                    {extract_self_class},
                     {declaring_classof_call},
                     {slot}
                 )));"
-            )]
-        } else {
-            vec![]
+                        )
+                    }))
+                    .collect_vec()
+            }
         };
 
         // if no params, just empty span
@@ -2509,16 +3385,27 @@ pub trait CSType: Sized {
             ),
         };
 
+        let find_method_call = |generics_array_cpp: &str| match config.method_lookup {
+            MethodLookup::Name => format!(
+                "{utils_namespace}::FindMethod(
+                    {declaring_classof_call},
+                    \"{m_name}\",
+                    {generics_array_cpp},
+                    {params_types_array_cpp}
+                )"
+            ),
+            MethodLookup::Token | MethodLookup::Address => format!(
+                "::cordl_internals::FindMethodByToken({declaring_classof_call}, 0x{:x})",
+                method.token.rid()
+            ),
+        };
+
         let method_info_lines = match &template {
             Some(template) => {
                 // generic
                 let template_names = template
                     .just_names()
-                    .map(|t| {
-                        format!(
-                            "::il2cpp_utils::il2cpp_type_check::il2cpp_no_arg_class<{t}>::get()"
-                        )
-                    })
+                    .map(|t| format!("{type_check}::il2cpp_no_arg_class<{t}>::get()"))
                     .join(", ");
                 let template_count = template.names.len();
 
@@ -2532,44 +3419,80 @@ pub trait CSType: Sized {
                 };
 
                 vec![
-                format!("static auto* ___internal_method_base = THROW_UNLESS((::il2cpp_utils::FindMethod(
-                    {declaring_classof_call},
-                    \"{m_name}\",
-                    {template_classes_array_cpp},
-                    {params_types_array_cpp}
-                )));"),
-                format!("static auto* {METHOD_INFO_VAR_NAME} = THROW_UNLESS(::il2cpp_utils::MakeGenericMethod(
+                format!("static auto* ___internal_method_base = THROW_UNLESS(({}));", find_method_call(&template_classes_array_cpp)),
+                format!("static auto* {METHOD_INFO_VAR_NAME} = THROW_UNLESS({utils_namespace}::MakeGenericMethod(
                     ___internal_method_base,
                     {template_classes_array_cpp}
                 ));"),
                 ]
             }
+            // generic instantiations share the method index, so they can't share a table entry
+            None if config.method_table && cpp_type.cpp_template.is_none() => {
+                cpp_type.method_table_entries.push(method_index);
+
+                vec![format!(
+                    "auto* {METHOD_INFO_VAR_NAME} = ::cordl_internals::method_table_get(0x{:x}, &{type_check}::il2cpp_no_arg_class<{}>::get, 0x{:x});",
+                    method_index.index(),
+                    cpp_type.cpp_name_components.combine_all(),
+                    method.token.rid()
+                )]
+            }
             None => {
-                vec![
-                    format!("static auto* {METHOD_INFO_VAR_NAME} = THROW_UNLESS((::il2cpp_utils::FindMethod(
-                        {declaring_classof_call},
-                        \"{m_name}\",
-                        std::span<const Il2CppClass* const, 0>(),
-                        {params_types_array_cpp}
-                    )));"),
-                    ]
+                vec![format!(
+                    "static auto* {METHOD_INFO_VAR_NAME} = THROW_UNLESS(({}));",
+                    find_method_call("std::span<const Il2CppClass* const, 0>()")
+                )]
             }
         };
 
-        let method_body_lines = [format!(
-            "return ::cordl_internals::RunMethodRethrow<{m_ret_cpp_type_name}, false>({});",
-            method_invoke_params
-                .into_iter()
-                .chain(param_names)
-                .join(", ")
-        )];
-
         // instance methods should resolve slots if this is an interface, or if this is a virtual/abstract method, and not a final method
         // static methods can't be virtual or interface anyway so checking for that here is irrelevant
         let should_resolve_slot = cpp_type.is_interface
             || ((method.is_virtual_method() || method.is_abstract_method())
                 && !method.is_final_method());
 
+        // generic code is shared or instantiated at runtime, so its address isn't known here
+        let direct_call_addrs = method_calc
+            .filter(|_| config.method_lookup == MethodLookup::Address)
+            .filter(|_| !should_resolve_slot && template.is_none() && !is_generic_method_inst)
+            .filter(|_| cpp_type.cpp_template.is_none())
+            .map(|m| m.addrs)
+            // abstract and extern methods have no body
+            .filter(|addrs| *addrs != 0);
+
+        let method_body_lines = match direct_call_addrs {
+            // native functions take the instance, the params and then the MethodInfo
+            Some(addrs) => {
+                let direct_call = format!(
+                    "return ::cordl_internals::RunMethodDirect<0x{addrs:x}, {m_ret_cpp_type_name}>({});",
+                    (!method.is_static_method())
+                        .then_some(instance_ptr.as_str())
+                        .into_iter()
+                        .chain(param_names)
+                        .chain([METHOD_INFO_VAR_NAME])
+                        .join(", ")
+                );
+
+                // il2cpp only runs the static constructor when invoking through the MethodInfo
+                match method.is_static_method() {
+                    true => vec![
+                        format!(
+                            "::cordl_internals::EnsureMethodClassInit({METHOD_INFO_VAR_NAME});"
+                        ),
+                        direct_call,
+                    ],
+                    false => vec![direct_call],
+                }
+            }
+            None => vec![format!(
+                "return ::cordl_internals::RunMethodRethrow<{m_ret_cpp_type_name}, false>({});",
+                method_invoke_params
+                    .into_iter()
+                    .chain(param_names)
+                    .join(", ")
+            )],
+        };
+
         let method_body = match should_resolve_slot {
             true => resolve_instance_slot_lines
                 .iter()
@@ -2646,7 +3569,7 @@ pub trait CSType: Sized {
                             estimated_size: method_calc.estimated_size,
                         },
                         interface_clazz_of: interface_declaring_cpp_type
-                            .map(|d| d.classof_cpp_name())
+                            .map(|d| d.classof_cpp_name(config))
                             .unwrap_or_else(|| format!("Bad stuff happened {declaring_type:?}")),
                         is_final: method.is_final_method(),
                         slot: if method.slot != u16::MAX {
@@ -2654,6 +3577,8 @@ pub trait CSType: Sized {
                         } else {
                             None
                         },
+                        utils_namespace: utils_namespace.clone(),
+                        out_of_line: false,
                     }
                     .into(),
                 )));
@@ -2668,6 +3593,10 @@ pub trait CSType: Sized {
                 .push(CppMember::MethodImpl(method_impl).into());
         }
 
+        if !is_generic_method_inst && template.is_none() && cpp_type.is_value_type {
+            Self::create_operator_forward(cpp_type, m_name, &method_decl, &declaring_type_template);
+        }
+
         if !is_generic_method_inst {
             cpp_type
                 .declarations
@@ -2675,6 +3604,73 @@ pub trait CSType: Sized {
         }
     }
 
+    ///
+    /// Exposes a C# operator method of a value type as the C++ operator,
+    /// e.g. `a + b` calls `op_Addition(a, b)`.
+    /// Only operators taking the declaring type as first parameter can be members,
+    /// the rest (e.g. `float * Vector3`) are left as named methods
+    ///
+    fn create_operator_forward(
+        cpp_type: &mut CppType,
+        m_name: &str,
+        method_decl: &CppMethodDecl,
+        declaring_type_template: &Option<CppTemplate>,
+    ) {
+        if method_decl.instance {
+            return;
+        }
+
+        let self_cpp_name = cpp_type.cpp_name_components.remove_pointer().combine_all();
+        let other_params = match method_decl.parameters.split_first() {
+            Some((first, rest)) if first.ty == self_cpp_name => rest,
+            _ => return,
+        };
+
+        // conversion operators are named by their target type
+        let is_conversion = matches!(m_name, "op_Implicit" | "op_Explicit");
+        let (cpp_name, return_type) = match is_conversion {
+            true if other_params.is_empty() => (method_decl.return_type.clone(), String::new()),
+            true => return,
+            false => match cpp_operator_name(m_name, method_decl.parameters.len()) {
+                Some(op) => (format!("operator{op}"), method_decl.return_type.clone()),
+                None => return,
+            },
+        };
+
+        let args = std::iter::once("*this")
+            .chain(CppParam::params_names(other_params).map(|s| s.as_str()))
+            .join(", ");
+        let body = format!("return {self_cpp_name}::{}({args});", method_decl.cpp_name);
+
+        let operator_decl = CppMethodDecl {
+            cpp_name,
+            return_type,
+            parameters: other_params.to_vec(),
+            instance: true,
+            is_const: true,
+            is_implicit_operator: m_name == "op_Implicit",
+            is_explicit_operator: m_name == "op_Explicit",
+            brief: Some(format!("Forwards to {}", method_decl.cpp_name)),
+            body: None,
+            ..method_decl.clone()
+        };
+
+        let operator_impl = CppMethodImpl {
+            body: vec![Arc::new(CppLine::make(body))],
+            declaring_cpp_full_name: self_cpp_name,
+            declaring_type_template: declaring_type_template.clone(),
+            is_operator: is_conversion,
+            ..operator_decl.clone().into()
+        };
+
+        cpp_type
+            .implementations
+            .push(CppMember::MethodImpl(operator_impl).into());
+        cpp_type
+            .declarations
+            .push(CppMember::MethodDecl(operator_decl).into());
+    }
+
     fn default_value_blob(
         metadata: &Metadata,
         ty: &Il2CppType,
@@ -2743,40 +3739,40 @@ pub trait CSType: Sized {
             // https://learn.microsoft.com/en-us/nimbusml/concepts/types
             // https://en.cppreference.com/w/cpp/types/floating-point
             Il2CppTypeEnum::R4 => {
-                let val = format!("{}", cursor.read_f32::<Endian>().unwrap());
-                if !val.contains('.')
-                    && val
-                        .find(|c: char| !c.is_ascii_digit() && c != '-')
-                        .is_none()
-                {
-                    val + ".0"
-                } else {
-                    val.replace("inf", "INFINITY").replace("NaN", "NAN")
+                let val = cursor.read_f32::<Endian>().unwrap();
+                match val.is_finite() {
+                    // debug formatting always keeps a fraction or exponent
+                    true => format!("{val:?}f"),
+                    false => float_special_value(val.into(), "float"),
                 }
             }
             Il2CppTypeEnum::R8 => {
-                let val = format!("{}", cursor.read_f64::<Endian>().unwrap());
-                if !val.contains('.')
-                    && val
-                        .find(|c: char| !c.is_ascii_digit() && c != '-')
-                        .is_none()
-                {
-                    val + ".0"
-                } else {
-                    val.replace("inf", "INFINITY").replace("NaN", "NAN")
+                let val = cursor.read_f64::<Endian>().unwrap();
+                match val.is_finite() {
+                    true => format!("{val:?}"),
+                    false => float_special_value(val, "double"),
                 }
             }
             Il2CppTypeEnum::Char => {
-                let res = String::from_utf16_lossy(&[cursor.read_u16::<Endian>().unwrap()])
-                    .escape_default()
-                    .to_string();
+                let val = cursor.read_u16::<Endian>().unwrap();
 
-                if string_quotes {
-                    let literal_prefix = if string_as_u16 { "u" } else { "" };
-                    return format!("{literal_prefix}'{res}'");
+                if !string_quotes {
+                    return String::from_utf16_lossy(&[val])
+                        .escape_default()
+                        .to_string();
                 }
 
-                res
+                // lone surrogates and other unprintable chars can't be written as a literal
+                match char::from_u32(val as u32).filter(|c| c.is_ascii_graphic() || *c == ' ') {
+                    Some(c) => {
+                        let literal_prefix = if string_as_u16 { "u" } else { "" };
+                        format!("{literal_prefix}'{}'", escape_cpp_literal(&c.to_string()))
+                    }
+                    None => match string_as_u16 {
+                        true => format!("static_cast<char16_t>(0x{val:x})"),
+                        false => format!("static_cast<char>(0x{val:x})"),
+                    },
+                }
             }
             Il2CppTypeEnum::String => {
                 // UTF-16 byte array len
@@ -2790,14 +3786,14 @@ pub trait CSType: Sized {
 
                 cursor.read_exact(buf.as_mut_slice()).unwrap();
 
-                let res = String::from_utf8(buf).unwrap().escape_default().to_string();
+                let res = String::from_utf8_lossy(&buf);
 
                 if string_quotes {
                     let literal_prefix = if string_as_u16 { "u" } else { "" };
-                    return format!("{literal_prefix}\"{res}\"");
+                    return format!("{literal_prefix}\"{}\"", escape_cpp_literal(&res));
                 }
 
-                res
+                res.escape_default().to_string()
             }
             // Il2CppTypeEnum::Genericinst => match ty.data {
             //     TypeData::GenericClassIndex(inst_idx) => {
@@ -2923,6 +3919,42 @@ pub trait CSType: Sized {
                 Self::default_value_blob(metadata, ty, def.data_index.index() as usize, true, true)
             })
     }
+    /// Bits of an integral constant, sign extended, used to inspect enum values
+    fn field_default_integer(metadata: &Metadata, field_index: FieldIndex) -> Option<u64> {
+        let def = metadata
+            .metadata
+            .global_metadata
+            .field_default_values
+            .as_vec()
+            .iter()
+            .find(|f| f.field_index == field_index)?;
+
+        if !def.data_index.is_valid() {
+            return Some(0);
+        }
+
+        let ty = &metadata.metadata_registration.types[def.type_index as usize];
+        let data = &metadata
+            .metadata
+            .global_metadata
+            .field_and_parameter_default_value_data
+            .as_vec()[def.data_index.index() as usize..];
+        let mut cursor = Cursor::new(data);
+
+        let value = match ty.ty {
+            Il2CppTypeEnum::I1 => cursor.read_i8().ok()? as u64,
+            Il2CppTypeEnum::U1 => cursor.read_u8().ok()? as u64,
+            Il2CppTypeEnum::I2 => cursor.read_i16::<Endian>().ok()? as u64,
+            Il2CppTypeEnum::U2 => cursor.read_u16::<Endian>().ok()? as u64,
+            Il2CppTypeEnum::I4 => cursor.read_compressed_i32::<Endian>().ok()? as u64,
+            Il2CppTypeEnum::U4 => cursor.read_u32::<Endian>().ok()? as u64,
+            Il2CppTypeEnum::I8 => cursor.read_i64::<Endian>().ok()? as u64,
+            Il2CppTypeEnum::U8 => cursor.read_u64::<Endian>().ok()?,
+            _ => return None,
+        };
+
+        Some(value)
+    }
     fn param_default_value(metadata: &Metadata, parameter_index: ParameterIndex) -> Option<String> {
         metadata
             .metadata
@@ -2969,7 +4001,12 @@ pub trait CSType: Sized {
             })
     }
 
-    fn il2cpp_byref(&mut self, cpp_name: String, typ: &Il2CppType) -> String {
+    fn il2cpp_byref(
+        &mut self,
+        cpp_name: String,
+        typ: &Il2CppType,
+        config: &GenerationConfig,
+    ) -> String {
         let requirements = &mut self.get_mut_cpp_type().requirements;
         // handle out T or
         // ref T when T is a value type
@@ -2977,14 +4014,14 @@ pub trait CSType: Sized {
         // typ.valuetype -> false when T&
         // apparently even if `T` is a valuetype
         if typ.is_param_out() || (typ.byref && !typ.valuetype) {
-            requirements.needs_byref_include();
-            return format!("ByRef<{cpp_name}>");
+            requirements.needs_byref_include(&config.runtime);
+            return format!("{}<{cpp_name}>", config.runtime.byref_type);
         }
 
         if typ.is_param_in() {
-            requirements.needs_byref_include();
+            requirements.needs_byref_include(&config.runtime);
 
-            return format!("ByRefConst<{cpp_name}>");
+            return format!("{}<{cpp_name}>", config.runtime.byref_const_type);
         }
 
         cpp_name
@@ -3037,6 +4074,7 @@ pub trait CSType: Sized {
         &mut self,
         ctx_collection: &CppContextCollection,
         metadata: &Metadata,
+        config: &GenerationConfig,
         typ: &Il2CppType,
         include_depth: usize,
         typ_usage: TypeUsage,
@@ -3049,6 +4087,7 @@ pub trait CSType: Sized {
             &mut requirements,
             ctx_collection,
             metadata,
+            config,
             typ,
             include_depth,
             cpp_type.generic_instantiations_args_types.as_ref(),
@@ -3066,6 +4105,7 @@ pub trait CSType: Sized {
         requirements: &mut CppTypeRequirements,
         ctx_collection: &CppContextCollection,
         metadata: &Metadata,
+        config: &GenerationConfig,
         typ: &Il2CppType,
         include_depth: usize,
         declaring_generic_inst_types: Option<&Vec<usize>>,
@@ -3093,6 +4133,7 @@ pub trait CSType: Sized {
             }
             Il2CppTypeEnum::R4 | Il2CppTypeEnum::R8 => {
                 requirements.needs_math_include();
+                requirements.needs_limits_include();
             }
             _ => (),
         };
@@ -3112,10 +4153,12 @@ pub trait CSType: Sized {
             // ptr types
             | Il2CppTypeEnum::I
             | Il2CppTypeEnum::U => {
-                let typ_cpp_tag: CppTypeTag = typ_tag.into();
+                let typ_cpp_tag: CppTypeTag = Self::get_tag_tdi(typ_tag).into();
 
                 // handle resolving indirection
-                let handle_resolving = |to_incl_cpp_ty: &CppType| -> NameComponents {
+                let handle_resolving = |to_incl_cpp_ty: &CppType,
+                                        requirements: &mut CppTypeRequirements|
+                 -> NameComponents {
                     let mut res = to_incl_cpp_ty.cpp_name_components.clone();
 
                     for resolve_handler in metadata.custom_type_resolve_handler.iter() {
@@ -3126,6 +4169,7 @@ pub trait CSType: Sized {
                             metadata,
                             typ,
                             typ_usage,
+                            requirements,
                         );
                     }
 
@@ -3134,7 +4178,7 @@ pub trait CSType: Sized {
 
                 // Self
                 if typ_cpp_tag == cpp_type.self_tag {
-                    return handle_resolving(cpp_type);
+                    return handle_resolving(cpp_type, requirements);
                 }
 
                 if let TypeData::TypeDefinitionIndex(tdi) = typ.data {
@@ -3154,7 +4198,7 @@ pub trait CSType: Sized {
                                 declaring_types: None,
                             };
                         }
-                        return wrapper_type_for_tdi(td).to_string().into();
+                        return wrapper_type_for_tdi(td, config).to_string().into();
                     }
                 }
 
@@ -3181,7 +4225,7 @@ pub trait CSType: Sized {
                 let typedef_incl = CppInclude::new_context_typedef(to_incl);
                 let typeimpl_incl = CppInclude::new_context_typeimpl(to_incl);
                 let to_incl_cpp_ty = ctx_collection
-                    .get_cpp_type(typ.data.into())
+                    .get_cpp_type(typ_cpp_tag)
                     .unwrap_or_else(|| panic!("Unable to get type to include {:?}", typ.data));
 
                 let own_context = other_context_ty == own_context_ty;
@@ -3214,7 +4258,7 @@ pub trait CSType: Sized {
                     }
                 }
 
-                handle_resolving(to_incl_cpp_ty)
+                handle_resolving(to_incl_cpp_ty, requirements)
 
                 // match to_incl_cpp_ty.is_enum_type || to_incl_cpp_ty.is_value_type {
                 //     true => ret,
@@ -3223,7 +4267,7 @@ pub trait CSType: Sized {
             }
             // Single dimension array
             Il2CppTypeEnum::Szarray => {
-                requirements.needs_arrayw_include();
+                requirements.needs_arrayw_include(&config.runtime);
 
                 let generic = match typ.data {
                     TypeData::TypeIndex(e) => {
@@ -3233,6 +4277,7 @@ pub trait CSType: Sized {
                             requirements,
                             ctx_collection,
                             metadata,
+                            config,
                             ty,
                             include_depth,
                             declaring_generic_inst_types,
@@ -3246,7 +4291,7 @@ pub trait CSType: Sized {
                 let generic_formatted = generic.combine_all();
 
                 NameComponents {
-                    name: "ArrayW".into(),
+                    name: config.runtime.array_type.clone(),
                     namespace: Some("".into()),
                     generics: Some(vec![
                         generic_formatted.clone(),
@@ -3305,6 +4350,7 @@ pub trait CSType: Sized {
                         requirements,
                         ctx_collection,
                         metadata,
+                        config,
                         ty,
                         include_depth,
                         declaring_generic_inst_types,
@@ -3407,11 +4453,13 @@ pub trait CSType: Sized {
                     };
 
                     if add_include {
-                        let generic_tag = CppTypeTag::from_type_data(typ.data, metadata.metadata);
-
                         // depend on both tdi and generic instantiation
                         requirements.add_dependency_tag(tdi.into());
-                        requirements.add_dependency_tag(generic_tag);
+
+                        match CppTypeTag::from_type_data(typ.data, metadata.metadata) {
+                            Ok(generic_tag) => requirements.add_dependency_tag(generic_tag),
+                            Err(e) => warn!("Unable to depend on generic inst {typ:?}: {e}"),
+                        }
                     }
 
                     let generic_types_formatted = new_generic_inst_types
@@ -3447,6 +4495,7 @@ pub trait CSType: Sized {
                                 requirements,
                                 ctx_collection,
                                 metadata,
+                                config,
                                 gen_arg_t,
                                 gen_include_detch,
                                 // use declaring generic inst since we're cppifying generic args
@@ -3462,6 +4511,7 @@ pub trait CSType: Sized {
                         requirements,
                         ctx_collection,
                         metadata,
+                        config,
                         generic_type_def,
                         include_depth,
                         Some(new_generic_inst_types),
@@ -3495,8 +4545,8 @@ pub trait CSType: Sized {
             Il2CppTypeEnum::Boolean => "bool".to_string().into(),
             Il2CppTypeEnum::Char => "char16_t".to_string().into(),
             Il2CppTypeEnum::String => {
-                requirements.needs_stringw_include();
-                "::StringW".to_string().into()
+                requirements.needs_stringw_include(&config.runtime);
+                config.runtime.string_type.clone().into()
             }
             Il2CppTypeEnum::Ptr => {
                 let generic = match typ.data {
@@ -3506,6 +4556,7 @@ pub trait CSType: Sized {
                             requirements,
                             ctx_collection,
                             metadata,
+                            config,
                             ty,
                             include_depth,
                             declaring_generic_inst_types,
@@ -3541,9 +4592,10 @@ pub trait CSType: Sized {
         ret
     }
 
-    fn classof_cpp_name(&self) -> String {
+    fn classof_cpp_name(&self, config: &GenerationConfig) -> String {
         format!(
-            "::il2cpp_utils::il2cpp_type_check::il2cpp_no_arg_class<{}>::get",
+            "{}::il2cpp_no_arg_class<{}>::get",
+            config.runtime.type_check_namespace(),
             self.get_cpp_type().cpp_name_components.combine_all()
         )
     }
@@ -3563,13 +4615,180 @@ pub trait CSType: Sized {
     }
 }
 
-fn wrapper_type_for_tdi(td: &Il2CppTypeDefinition) -> &str {
+/// The C++ operator of a C# operator method taking `arity` parameters
+fn cpp_operator_name(m_name: &str, arity: usize) -> Option<&'static str> {
+    let op = match (m_name, arity) {
+        ("op_UnaryPlus", 1) => "+",
+        ("op_UnaryNegation", 1) => "-",
+        ("op_LogicalNot", 1) => "!",
+        ("op_OnesComplement", 1) => "~",
+
+        ("op_Addition", 2) => "+",
+        ("op_Subtraction", 2) => "-",
+        ("op_Multiply", 2) => "*",
+        ("op_Division", 2) => "/",
+        ("op_Modulus", 2) => "%",
+        ("op_BitwiseAnd", 2) => "&",
+        ("op_BitwiseOr", 2) => "|",
+        ("op_ExclusiveOr", 2) => "^",
+        ("op_LeftShift", 2) => "<<",
+        ("op_RightShift", 2) => ">>",
+
+        ("op_Equality", 2) => "==",
+        ("op_Inequality", 2) => "!=",
+        ("op_LessThan", 2) => "<",
+        ("op_GreaterThan", 2) => ">",
+        ("op_LessThanOrEqual", 2) => "<=",
+        ("op_GreaterThanOrEqual", 2) => ">=",
+        _ => return None,
+    };
+
+    Some(op)
+}
+
+/// NaN and infinities have no literal, `float_type` picks the `numeric_limits` to use
+fn float_special_value(val: f64, float_type: &str) -> String {
+    let limits = format!("::std::numeric_limits<{float_type}>");
+
+    match val {
+        v if v.is_nan() => format!("{limits}::quiet_NaN()"),
+        v if v.is_sign_positive() => format!("{limits}::infinity()"),
+        _ => format!("-{limits}::infinity()"),
+    }
+}
+
+///
+/// Escapes a string for a C++ string or char literal.
+/// Control chars use octal escapes, as hex escapes would swallow following hex digits,
+/// and non ASCII chars use universal character names
+///
+pub(crate) fn escape_cpp_literal(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\'' => escaped.push_str("\\'"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if c.is_ascii_graphic() || c == ' ' => escaped.push(c),
+            c if c.is_ascii() => escaped.push_str(&format!("\\{:03o}", c as u32)),
+            c if (c as u32) <= 0xFFFF => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push_str(&format!("\\U{:08x}", c as u32)),
+        }
+    }
+
+    escaped
+}
+
+///
+/// Finds the method that introduced `slot`, walking up from `tdi` through its parents.
+/// A slot within the interface offsets of a type belongs to that interface,
+/// otherwise the topmost base class declaring a method with the slot introduced it.
+/// Returns None if the slot is introduced by `tdi` itself
+///
+fn find_slot_origin(
+    metadata: &Metadata,
+    tdi: TypeDefinitionIndex,
+    slot: u16,
+) -> Option<SlotOrigin<'_>> {
+    let global_metadata = &metadata.metadata.global_metadata;
+    let mut origin = None;
+    let mut current = Some(tdi);
+
+    while let Some(current_tdi) = current {
+        let t = &global_metadata.type_definitions[current_tdi];
+        if slot >= t.vtable_count {
+            break;
+        }
+
+        let interface_offsets_start = t.interface_offsets_start as usize;
+        let interface_offsets = &global_metadata.interface_offsets.as_vec()
+            [interface_offsets_start..interface_offsets_start + t.interface_offsets_count as usize];
+
+        for pair in interface_offsets {
+            let interface_ty =
+                &metadata.metadata_registration.types[pair.interface_type_index as usize];
+            let Some(interface_tdi) = metadata.type_tdi(interface_ty) else {
+                continue;
+            };
+            let interface_td = &global_metadata.type_definitions[interface_tdi];
+
+            // interface vtables only hold the virtual methods, not static or generic ones
+            let Ok(offset) = u16::try_from(pair.offset) else {
+                continue;
+            };
+            let Some(end) = offset.checked_add(interface_td.vtable_count) else {
+                continue;
+            };
+            if !(offset..end).contains(&slot) {
+                continue;
+            }
+
+            // the interface type is in the generic context of current_tdi, which only we can name
+            if current_tdi != tdi && t.generic_container_index.is_valid() {
+                return origin;
+            }
+
+            let interface_slot = slot - offset;
+            let method_index = interface_td
+                .methods(metadata.metadata)
+                .iter()
+                .position(|m| m.slot == interface_slot)
+                .map(|i| MethodIndex::new(interface_td.method_start.index() + i as u32));
+
+            return Some(SlotOrigin::Interface {
+                ty: interface_ty,
+                slot: interface_slot,
+                method_index,
+            });
+        }
+
+        if current_tdi != tdi {
+            if let Some(i) = t
+                .methods(metadata.metadata)
+                .iter()
+                .position(|m| m.slot == slot)
+            {
+                origin = Some(SlotOrigin::Base {
+                    tdi: current_tdi,
+                    method_index: MethodIndex::new(t.method_start.index() + i as u32),
+                });
+            }
+        }
+
+        current = (t.parent_index != u32::MAX)
+            .then(|| &metadata.metadata_registration.types[t.parent_index as usize])
+            .and_then(|parent| metadata.type_tdi(parent));
+    }
+
+    origin
+}
+
+/// What a virtual method implements, see [`find_slot_origin`]
+enum SlotOrigin<'a> {
+    /// an interface method, `slot` is relative to the interface
+    Interface {
+        ty: &'a Il2CppType,
+        slot: u16,
+        method_index: Option<MethodIndex>,
+    },
+    /// a base class method with the same vtable slot
+    Base {
+        tdi: TypeDefinitionIndex,
+        method_index: MethodIndex,
+    },
+}
+
+fn wrapper_type_for_tdi<'a>(td: &Il2CppTypeDefinition, config: &'a GenerationConfig) -> &'a str {
     if td.is_enum_type() {
-        return ENUM_WRAPPER_TYPE;
+        return &config.runtime.enum_wrapper_type;
     }
 
     if td.is_value_type() {
-        return VALUE_WRAPPER_TYPE;
+        return &config.runtime.value_wrapper_type;
     }
 
     if td.is_interface() {
@@ -3589,14 +4808,12 @@ fn parse_generic_arg(
     cpp_type: &mut CppType,
     ctx_collection: &CppContextCollection,
     metadata: &Metadata<'_>,
+    config: &GenerationConfig,
     template_args: &mut Vec<(String, String)>,
 ) -> NameComponents {
     // If reference type, we use a template and add a requirement
     if !t.valuetype {
-        template_args.push((
-            CORDL_REFERENCE_TYPE_CONSTRAINT.to_string(),
-            gen_name.clone(),
-        ));
+        template_args.push((config.runtime.reference_type_constraint(), gen_name.clone()));
         return gen_name.into();
     }
 
@@ -3664,6 +4881,7 @@ fn parse_generic_arg(
             .cppify_name_il2cpp(
                 ctx_collection,
                 metadata,
+                config,
                 &inner_enum_type,
                 0,
                 TypeUsage::GenericArg,
@@ -3679,7 +4897,7 @@ fn parse_generic_arg(
     }
 
     let inner_type =
-        cpp_type.cppify_name_il2cpp(ctx_collection, metadata, t, 0, TypeUsage::TypeName);
+        cpp_type.cppify_name_il2cpp(ctx_collection, metadata, config, t, 0, TypeUsage::TypeName);
 
     match t.data {
         TypeData::GenericClassIndex(gen_class_idx) => {
@@ -3699,6 +4917,7 @@ fn parse_generic_arg(
             let non_generic_inner_type = cpp_type.cppify_name_il2cpp(
                 ctx_collection,
                 metadata,
+                config,
                 gen_class_ty,
                 0,
                 TypeUsage::GenericArg,
@@ -3728,6 +4947,7 @@ fn parse_generic_arg(
                         cpp_type,
                         ctx_collection,
                         metadata,
+                        config,
                         template_args,
                     )
                 })
@@ -0,0 +1,45 @@
+use std::{fs::File, io::BufWriter, path::Path};
+
+use color_eyre::eyre::Result;
+use itertools::Itertools;
+use log::error;
+use serde::Serialize;
+
+use super::cpp_type_tag::CppTypeTag;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TypeFailureStage {
+    Make,
+    Fill,
+}
+
+/// A type that failed to generate and was downgraded to an opaque type
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeFailure {
+    #[serde(skip)]
+    pub tag: CppTypeTag,
+    pub full_name: String,
+    pub stage: TypeFailureStage,
+    pub reason: String,
+}
+
+pub fn write_failure_report(failures: &[TypeFailure], file: &Path) -> Result<()> {
+    for failure in failures {
+        error!(
+            "Failed to {:?} {}: {}",
+            failure.stage, failure.full_name, failure.reason
+        );
+    }
+
+    let failures = failures
+        .iter()
+        .sorted_by(|a, b| a.full_name.cmp(&b.full_name))
+        .collect_vec();
+
+    let file = File::create(file)?;
+    let mut buf_writer = BufWriter::new(file);
+
+    serde_json::to_writer_pretty(&mut buf_writer, &failures)?;
+
+    Ok(())
+}
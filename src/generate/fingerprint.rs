@@ -0,0 +1,209 @@
+//! Records which global-metadata.dat and libil2cpp.so the headers were generated from.
+//!
+//! `cordl_version.hpp` holds the fingerprint, and every namespace header asserts it was generated
+//! with the same one, so a partially regenerated tree fails to compile.
+//! The build-id can also be compared against the libil2cpp.so loaded at runtime.
+
+use std::{fs::File, io::Write, path::Path};
+
+use color_eyre::eyre::Result;
+use itertools::Itertools;
+
+pub const VERSION_HEADER: &str = "cordl_version.hpp";
+
+const METADATA_SANITY: u32 = 0xFAB11BAF;
+
+const PT_NOTE: u32 = 4;
+const NT_GNU_BUILD_ID: u32 = 3;
+
+#[derive(Debug, Clone)]
+pub struct InputFingerprint {
+    pub metadata_version: Option<u32>,
+    pub metadata_hash: u64,
+    pub libil2cpp_hash: u64,
+    /// Lowercase hex of the GNU build-id note, if the library has one
+    pub libil2cpp_build_id: Option<String>,
+}
+
+impl InputFingerprint {
+    pub fn new(metadata_data: &[u8], elf_data: &[u8]) -> Self {
+        Self {
+            metadata_version: metadata_version(metadata_data),
+            metadata_hash: fnv1a_64(metadata_data),
+            libil2cpp_hash: fnv1a_64(elf_data),
+            libil2cpp_build_id: elf_build_id(elf_data),
+        }
+    }
+
+    /// One line summary, for comments and logs
+    pub fn describe(&self) -> String {
+        format!(
+            "metadata v{} 0x{:016x}, libil2cpp 0x{:016x} build-id {}",
+            self.metadata_version
+                .map_or_else(|| "?".to_string(), |v| v.to_string()),
+            self.metadata_hash,
+            self.libil2cpp_hash,
+            self.libil2cpp_build_id.as_deref().unwrap_or("none")
+        )
+    }
+
+    /// Condition checking that `cordl_version.hpp` has this fingerprint
+    pub fn static_assert_condition(&self) -> String {
+        format!(
+            "::cordl_version::metadata_hash == 0x{:016x} && ::cordl_version::libil2cpp_hash == 0x{:016x}",
+            self.metadata_hash, self.libil2cpp_hash
+        )
+    }
+
+    pub fn write_version_header(&self, header_path: &Path) -> Result<()> {
+        let mut file = File::create(header_path.join(VERSION_HEADER))?;
+
+        writeln!(file, "#pragma once")?;
+        writeln!(file)?;
+        writeln!(file, "#include \"cordl_internals/version-check.hpp\"")?;
+        writeln!(file)?;
+        writeln!(file, "#include <cstdint>")?;
+        writeln!(file, "#include <optional>")?;
+        writeln!(file, "#include <string_view>")?;
+        writeln!(file)?;
+        writeln!(file, "// Generated by cordl from {}", self.describe())?;
+        writeln!(file, "namespace cordl_version {{")?;
+        writeln!(
+            file,
+            "  constexpr std::uint32_t metadata_version = {};",
+            self.metadata_version.unwrap_or_default()
+        )?;
+        writeln!(
+            file,
+            "  constexpr std::uint64_t metadata_hash = 0x{:016x};",
+            self.metadata_hash
+        )?;
+        writeln!(
+            file,
+            "  constexpr std::uint64_t libil2cpp_hash = 0x{:016x};",
+            self.libil2cpp_hash
+        )?;
+        writeln!(file, "  /// empty if libil2cpp.so has no build-id")?;
+        writeln!(
+            file,
+            "  constexpr std::string_view libil2cpp_build_id = \"{}\";",
+            self.libil2cpp_build_id.as_deref().unwrap_or_default()
+        )?;
+        writeln!(file)?;
+        writeln!(
+            file,
+            "  /// @brief whether the loaded libil2cpp.so is the one these headers were generated from"
+        )?;
+        writeln!(file, "  /// @return nullopt if either build-id is unknown")?;
+        writeln!(
+            file,
+            "  inline std::optional<bool> matches_loaded_libil2cpp() {{"
+        )?;
+        writeln!(
+            file,
+            "    return matches_loaded_build_id(libil2cpp_build_id, \"libil2cpp.so\");"
+        )?;
+        writeln!(file, "  }}")?;
+        writeln!(file, "}} // namespace cordl_version")?;
+
+        Ok(())
+    }
+}
+
+/// FNV-1a, only used to tell inputs apart
+fn fnv1a_64(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn metadata_version(data: &[u8]) -> Option<u32> {
+    if read_u32(data, 0)? != METADATA_SANITY {
+        return None;
+    }
+
+    read_u32(data, 4)
+}
+
+/// The GNU build-id note of a little endian ELF, found through its PT_NOTE segments
+fn elf_build_id(elf: &[u8]) -> Option<String> {
+    if elf.get(..4)? != b"\x7fELF" || *elf.get(5)? != 1 {
+        return None;
+    }
+
+    let is_64 = match elf.get(4)? {
+        1 => false,
+        2 => true,
+        _ => return None,
+    };
+
+    let (phoff, phentsize, phnum) = match is_64 {
+        true => (
+            read_u64(elf, 0x20)?,
+            read_u16(elf, 0x36)?,
+            read_u16(elf, 0x38)?,
+        ),
+        false => (
+            read_u32(elf, 0x1c)? as u64,
+            read_u16(elf, 0x2a)?,
+            read_u16(elf, 0x2c)?,
+        ),
+    };
+
+    (0..phnum as usize).find_map(|i| {
+        let phdr = phoff as usize + i * phentsize as usize;
+        if read_u32(elf, phdr)? != PT_NOTE {
+            return None;
+        }
+
+        let (offset, size) = match is_64 {
+            true => (read_u64(elf, phdr + 0x8)?, read_u64(elf, phdr + 0x20)?),
+            false => (
+                read_u32(elf, phdr + 0x4)? as u64,
+                read_u32(elf, phdr + 0x10)? as u64,
+            ),
+        };
+
+        find_build_id_note(elf.get(offset as usize..(offset + size) as usize)?)
+    })
+}
+
+fn find_build_id_note(mut notes: &[u8]) -> Option<String> {
+    let align = |n: usize| (n + 3) & !3;
+
+    while notes.len() >= 12 {
+        let name_size = read_u32(notes, 0)? as usize;
+        let desc_size = read_u32(notes, 4)? as usize;
+        let note_type = read_u32(notes, 8)?;
+
+        let name = notes.get(12..12 + name_size)?;
+        let desc_start = 12 + align(name_size);
+        let desc = notes.get(desc_start..desc_start + desc_size)?;
+
+        if note_type == NT_GNU_BUILD_ID && name == b"GNU\0" {
+            return Some(desc.iter().map(|b| format!("{b:02x}")).join(""));
+        }
+
+        notes = notes.get(desc_start + align(desc_size)..)?;
+    }
+
+    None
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        data.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
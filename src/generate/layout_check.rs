@@ -0,0 +1,354 @@
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use brocolib::{
+    global_metadata::{Il2CppTypeDefinition, TypeDefinitionIndex},
+    runtime_metadata::{Il2CppType, Il2CppTypeEnum, TypeData},
+};
+use color_eyre::eyre::Result;
+use itertools::Itertools;
+use log::{info, warn};
+
+use crate::helpers::unwind::catch_panic;
+
+use super::{
+    metadata::Metadata,
+    offsets,
+    type_extensions::{TypeDefinitionExtensions, TypeExtentions},
+};
+
+#[derive(Debug, Clone)]
+pub enum LayoutMismatchKind {
+    InstanceSize {
+        expected: u32,
+        computed: u32,
+    },
+    FieldOffset {
+        field: String,
+        expected: u32,
+        computed: u32,
+    },
+    FieldCount {
+        expected: usize,
+        computed: usize,
+    },
+    /// Laying out the type panicked
+    LayoutFailed(String),
+    /// Uses the named type, whose layout is inconsistent, in a way that breaks this type too
+    DependsOn(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct LayoutMismatch {
+    pub tdi: TypeDefinitionIndex,
+    pub full_name: String,
+    pub kind: LayoutMismatchKind,
+}
+
+///
+/// Compares the layout computed by `offsets::layout_fields` against
+/// the instance sizes and field offsets stored in the runtime metadata.
+/// Generic type definitions and interfaces are skipped,
+/// since the runtime has no concrete layout for them.
+/// Interfaces that use a mismatched type are found by [`find_layout_dependents`] instead
+///
+pub fn find_layout_mismatches(metadata: &Metadata) -> Vec<LayoutMismatch> {
+    let type_defs = metadata.metadata.global_metadata.type_definitions.as_vec();
+
+    type_defs
+        .iter()
+        .enumerate()
+        .map(|(i, td)| (TypeDefinitionIndex::new(i as u32), td))
+        .filter(|(_, td)| !td.is_interface() && !td.generic_container_index.is_valid())
+        .flat_map(|(tdi, td)| {
+            let full_name = td.full_name(metadata.metadata, true);
+            let make_mismatch = |kind| LayoutMismatch {
+                tdi,
+                full_name: full_name.clone(),
+                kind,
+            };
+
+            let Some(size_metadata) = offsets::get_size_of_type_table(metadata, tdi) else {
+                return vec![];
+            };
+            // cordl computes the layout itself for these
+            if size_metadata.instance_size == 0 {
+                return vec![];
+            }
+
+            let mut computed_offsets: Vec<u32> = vec![];
+            let layout = catch_panic(|| {
+                Ok(offsets::layout_fields(
+                    metadata,
+                    td,
+                    tdi,
+                    None,
+                    Some(&mut computed_offsets),
+                    // otherwise explicit layout types report the metadata size
+                    true,
+                ))
+            });
+
+            let layout = match layout {
+                Ok(layout) => layout,
+                Err(e) => {
+                    return vec![make_mismatch(LayoutMismatchKind::LayoutFailed(
+                        e.to_string(),
+                    ))];
+                }
+            };
+
+            let mut mismatches = vec![];
+
+            // explicitly sized types (e.g. fixed buffers) are generated with the metadata size,
+            // only their field offsets can be wrong
+            if !offsets::has_explicit_size(metadata, td)
+                && layout.size as u32 != size_metadata.instance_size
+            {
+                mismatches.push(make_mismatch(LayoutMismatchKind::InstanceSize {
+                    expected: size_metadata.instance_size,
+                    computed: layout.size as u32,
+                }));
+            }
+
+            let Some(field_offsets) = metadata
+                .metadata_registration
+                .field_offsets
+                .as_ref()
+                .and_then(|f| f.get(tdi.index() as usize))
+            else {
+                return mismatches;
+            };
+
+            // the runtime offset table is indexed by all fields,
+            // while layout_fields only emits offsets for instance fields
+            let instance_fields = td
+                .fields(metadata.metadata)
+                .iter()
+                .enumerate()
+                .filter(|(_, f)| {
+                    let f_type = &metadata.metadata_registration.types[f.type_index as usize];
+                    !f_type.is_static() && !f_type.is_constant()
+                })
+                .collect_vec();
+
+            if instance_fields.len() != computed_offsets.len() {
+                mismatches.push(make_mismatch(LayoutMismatchKind::FieldCount {
+                    expected: instance_fields.len(),
+                    computed: computed_offsets.len(),
+                }));
+                return mismatches;
+            }
+
+            mismatches.extend(
+                instance_fields
+                    .into_iter()
+                    .zip(computed_offsets)
+                    .filter_map(|((i, f), computed)| {
+                        let expected = *field_offsets.get(i)?;
+                        (expected != computed).then(|| {
+                            make_mismatch(LayoutMismatchKind::FieldOffset {
+                                field: f.name(metadata.metadata).to_string(),
+                                expected,
+                                computed,
+                            })
+                        })
+                    }),
+            );
+
+            mismatches
+        })
+        .collect_vec()
+}
+
+///
+/// Types that break because they use a type from `mismatches`:
+/// types holding one in an instance field by value or by pointer,
+/// interfaces taking or returning one, and types implementing such an interface
+/// or an interface instantiated with one.
+/// Repeated until nothing new is found, since these chain
+/// (e.g. a struct holding a struct that holds a mismatched struct).
+/// This is how interfaces, which have no layout of their own, get caught
+///
+pub fn find_layout_dependents(
+    metadata: &Metadata,
+    mismatches: &[LayoutMismatch],
+) -> Vec<LayoutMismatch> {
+    let type_defs = metadata.metadata.global_metadata.type_definitions.as_vec();
+
+    let mut quarantined: HashSet<TypeDefinitionIndex> = mismatches.iter().map(|m| m.tdi).collect();
+    let mut dependents = vec![];
+
+    loop {
+        let found = type_defs
+            .iter()
+            .enumerate()
+            .map(|(i, td)| (TypeDefinitionIndex::new(i as u32), td))
+            .filter(|(tdi, _)| !quarantined.contains(tdi))
+            .filter_map(|(tdi, td)| {
+                let dependency = layout_dependency(metadata, td, &quarantined)?;
+
+                Some(LayoutMismatch {
+                    tdi,
+                    full_name: td.full_name(metadata.metadata, true),
+                    kind: LayoutMismatchKind::DependsOn(
+                        type_defs[dependency.index() as usize].full_name(metadata.metadata, true),
+                    ),
+                })
+            })
+            .collect_vec();
+
+        if found.is_empty() {
+            break;
+        }
+
+        quarantined.extend(found.iter().map(|m| m.tdi));
+        dependents.extend(found);
+    }
+
+    dependents
+}
+
+/// The quarantined type `td` depends on, if any
+fn layout_dependency(
+    metadata: &Metadata,
+    td: &Il2CppTypeDefinition,
+    quarantined: &HashSet<TypeDefinitionIndex>,
+) -> Option<TypeDefinitionIndex> {
+    let types = &metadata.metadata_registration.types;
+    let type_defs = &metadata.metadata.global_metadata.type_definitions;
+
+    // blacklisted classes are used as Il2CppObject*, only value types change the layout or ABI
+    let quarantined_value_type = |ty: &Il2CppType| {
+        referenced_tdis(metadata, ty)
+            .into_iter()
+            .find(|tdi| quarantined.contains(tdi) && type_defs[*tdi].is_value_type())
+    };
+
+    let field_dependency = td
+        .fields(metadata.metadata)
+        .iter()
+        .map(|f| &types[f.type_index as usize])
+        .filter(|ty| !ty.is_static() && !ty.is_constant())
+        .filter(|ty| ty.valuetype || ty.ty == Il2CppTypeEnum::Ptr)
+        .find_map(quarantined_value_type);
+    if field_dependency.is_some() {
+        return field_dependency;
+    }
+
+    if td.is_interface() {
+        let signature_dependency = td.methods(metadata.metadata).iter().find_map(|m| {
+            m.parameters(metadata.metadata)
+                .iter()
+                .map(|p| &types[p.type_index as usize])
+                .chain([&types[m.return_type as usize]])
+                .find_map(quarantined_value_type)
+        });
+        if signature_dependency.is_some() {
+            return signature_dependency;
+        }
+    }
+
+    td.interfaces(metadata.metadata)
+        .iter()
+        .flat_map(|&interface_index| referenced_tdis(metadata, &types[interface_index as usize]))
+        .find(|tdi| quarantined.contains(tdi))
+}
+
+/// The type definitions `ty` is made of: itself, what it points to and its generic arguments
+fn referenced_tdis(metadata: &Metadata, ty: &Il2CppType) -> Vec<TypeDefinitionIndex> {
+    match ty.data {
+        TypeData::TypeDefinitionIndex(tdi) => vec![tdi],
+        TypeData::TypeIndex(inner) if ty.ty == Il2CppTypeEnum::Ptr => {
+            referenced_tdis(metadata, &metadata.metadata_registration.types[inner])
+        }
+        TypeData::GenericClassIndex(gen_class_idx) => {
+            let gen_class = &metadata.metadata_registration.generic_classes[gen_class_idx];
+            let args = gen_class
+                .context
+                .class_inst_idx
+                .map(|inst| &metadata.metadata_registration.generic_insts[inst].types)
+                .into_iter()
+                .flatten()
+                .flat_map(|&arg| {
+                    referenced_tdis(metadata, &metadata.metadata_registration.types[arg])
+                });
+
+            metadata.type_tdi(ty).into_iter().chain(args).collect()
+        }
+        _ => vec![],
+    }
+}
+
+pub fn write_layout_report(mismatches: &[LayoutMismatch], file: &Path) -> Result<()> {
+    let file = File::create(file)?;
+    let mut buf_writer = BufWriter::new(file);
+
+    for (full_name, type_mismatches) in &mismatches
+        .iter()
+        .sorted_by(|a, b| a.full_name.cmp(&b.full_name))
+        .chunk_by(|m| &m.full_name)
+    {
+        writeln!(buf_writer, "{full_name}")?;
+        for mismatch in type_mismatches {
+            match &mismatch.kind {
+                LayoutMismatchKind::InstanceSize { expected, computed } => writeln!(
+                    buf_writer,
+                    "\tinstance size: expected 0x{expected:x}, computed 0x{computed:x}"
+                )?,
+                LayoutMismatchKind::FieldOffset {
+                    field,
+                    expected,
+                    computed,
+                } => writeln!(
+                    buf_writer,
+                    "\tfield {field}: expected offset 0x{expected:x}, computed 0x{computed:x}"
+                )?,
+                LayoutMismatchKind::FieldCount { expected, computed } => writeln!(
+                    buf_writer,
+                    "\tinstance field count: expected {expected}, computed {computed}"
+                )?,
+                LayoutMismatchKind::LayoutFailed(reason) => {
+                    writeln!(buf_writer, "\tlayout failed: {reason}")?
+                }
+                LayoutMismatchKind::DependsOn(dependency) => writeln!(
+                    buf_writer,
+                    "\tuses {dependency}, which has an inconsistent layout"
+                )?,
+            }
+        }
+    }
+
+    Ok(())
+}
+
+///
+/// Blacklists every type whose computed layout disagrees with the runtime metadata,
+/// along with the types depending on them, and writes the mismatches to `report_file`
+///
+pub fn quarantine_layout_mismatches(metadata: &mut Metadata, report_file: &Path) -> Result<()> {
+    let mut mismatches = find_layout_mismatches(metadata);
+    let dependents = find_layout_dependents(metadata, &mismatches);
+    mismatches.extend(dependents);
+
+    for (tdi, full_name) in mismatches
+        .iter()
+        .map(|m| (m.tdi, &m.full_name))
+        .unique_by(|(tdi, _)| *tdi)
+    {
+        warn!("Blacklisted {full_name} due to inconsistent layout");
+        metadata.blacklisted_types.insert(tdi);
+    }
+
+    info!(
+        "Found {} layout mismatches, writing report to {report_file:?}",
+        mismatches.len()
+    );
+    write_layout_report(&mismatches, report_file)?;
+
+    Ok(())
+}
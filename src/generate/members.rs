@@ -1,12 +1,9 @@
 use itertools::Itertools;
 use pathdiff::diff_paths;
 
-use crate::STATIC_CONFIG;
 
 use super::{
-    context::CppContext,
-    cpp_type::{CppType, CORDL_REFERENCE_TYPE_CONSTRAINT},
-    writer::Writable,
+    context::CppContext, cpp_type::CppType, runtime_profile::RuntimeProfile, writer::Writable,
 };
 
 use std::{
@@ -31,11 +28,11 @@ impl CppTemplate {
                 .collect(),
         }
     }
-    pub fn make_ref_types(names: impl Iterator<Item = String>) -> Self {
+    pub fn make_ref_types(names: impl Iterator<Item = String>, runtime: &RuntimeProfile) -> Self {
         CppTemplate {
             names: names
                 .into_iter()
-                .map(|s| (CORDL_REFERENCE_TYPE_CONSTRAINT.to_string(), s))
+                .map(|s| (runtime.reference_type_constraint(), s))
                 .collect(),
         }
     }
@@ -165,6 +162,11 @@ pub struct CppMethodSizeStruct {
     pub interface_clazz_of: String,
     pub is_final: bool,
     pub slot: Option<u16>,
+
+    /// Namespace of the runtime's il2cpp helpers
+    pub utils_namespace: String,
+    /// Whether `methodInfo()` is defined in the type's `.cpp` source file
+    pub out_of_line: bool,
 }
 #[derive(Clone, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct CppFieldDecl {
@@ -625,30 +627,32 @@ impl CppParam {
         params.iter().map(|p| &p.ty)
     }
 
-    pub fn params_il2cpp_types(params: &[CppParam]) -> impl Iterator<Item = String> + '_ {
+    pub fn params_il2cpp_types<'a>(
+        params: &'a [CppParam],
+        runtime: &'a RuntimeProfile,
+    ) -> impl Iterator<Item = String> + 'a {
         params
             .iter()
-            .map(|p| format!("::il2cpp_utils::ExtractType({})", p.name))
+            .map(|p| format!("{}::ExtractType({})", runtime.utils_namespace, p.name))
     }
 }
 
 impl CppInclude {
-    // smelly use of config but whatever
     pub fn new_context_typedef(context: &CppContext) -> Self {
         Self {
-            include: diff_paths(&context.typedef_path, &STATIC_CONFIG.header_path).unwrap(),
+            include: diff_paths(&context.typedef_path, &context.header_path).unwrap(),
             system: false,
         }
     }
     pub fn new_context_typeimpl(context: &CppContext) -> Self {
         Self {
-            include: diff_paths(&context.type_impl_path, &STATIC_CONFIG.header_path).unwrap(),
+            include: diff_paths(&context.type_impl_path, &context.header_path).unwrap(),
             system: false,
         }
     }
     pub fn new_context_fundamental(context: &CppContext) -> Self {
         Self {
-            include: diff_paths(&context.fundamental_path, &STATIC_CONFIG.header_path).unwrap(),
+            include: diff_paths(&context.fundamental_path, &context.header_path).unwrap(),
             system: false,
         }
     }
@@ -605,25 +605,68 @@ impl Writable for CppMethodSizeStruct {
         )?;
         let template = self.template.clone().unwrap_or_default();
 
-        let complete_type_name = &self.declaring_type_name;
-        let classof_call = &self.declaring_classof_call;
-        let cpp_method_name = &self.cpp_method_name;
-        let ret_type = &self.ret_ty;
         let size = &self.method_data.estimated_size;
         let addr = &self.method_data.addrs;
+        let utils_namespace = &self.utils_namespace;
+        let method_ptr = self.method_ptr();
+
+        let method_info = match self.out_of_line {
+            true => "static const ::MethodInfo* methodInfo();".to_string(),
+            false => format!(
+                "inline static const ::MethodInfo* methodInfo() {{
+    {}
+  }}",
+                self.method_info_body()
+            ),
+        };
 
-        let interface_klass_of = &self.interface_clazz_of;
+        template.write(writer)?;
+
+        writeln!(
+            writer,
+            "
+struct CORDL_HIDDEN {utils_namespace}::il2cpp_type_check::MetadataGetter<{method_ptr}> {{
+  constexpr static std::size_t size = 0x{size:x};
+  constexpr static std::size_t addrs = 0x{addr:x};
 
+  {method_info}
+}};"
+        )?;
+        Ok(())
+    }
+}
+
+impl CppMethodSizeStruct {
+    /// `static_cast` of the method to its member function pointer type, which the `MetadataGetter` is specialized on
+    fn method_ptr(&self) -> String {
+        let complete_type_name = &self.declaring_type_name;
+        let cpp_method_name = &self.cpp_method_name;
+        let ret_type = &self.ret_ty;
         let params_format = CppParam::params_types(&self.params).join(", ");
 
+        let f_ptr_prefix = if self.instance {
+            format!("{complete_type_name}::")
+        } else {
+            "".to_string()
+        };
+
+        format!(
+            "static_cast<{ret_type} ({f_ptr_prefix}*)({params_format})>(&{complete_type_name}::{cpp_method_name})"
+        )
+    }
+
+    fn method_info_body(&self) -> String {
+        let classof_call = &self.declaring_classof_call;
+        let interface_klass_of = &self.interface_clazz_of;
         let method_info_var = &self.method_info_var;
+        let utils_namespace = &self.utils_namespace;
 
         // if we have a slot, this isn't final and we aren't an interface, do a slot resolve
         // interface classes don't actually have vtables to perform a slot resolve on (count == 0)
         let method_info_lines = if let Some(slot) = self.slot && !self.is_final {
             vec![
                 format!("
-                            static auto* {method_info_var} = THROW_UNLESS(::il2cpp_utils::ResolveVtableSlot(
+                            static auto* {method_info_var} = THROW_UNLESS({utils_namespace}::ResolveVtableSlot(
                                 {classof_call},
                                  {interface_klass_of}(),
                                   {slot}
@@ -633,26 +676,54 @@ impl Writable for CppMethodSizeStruct {
             self.method_info_lines.clone()
         }.join("\n");
 
-        let f_ptr_prefix = if self.instance {
-            format!("{}::", self.declaring_type_name)
-        } else {
-            "".to_string()
-        };
+        format!("{method_info_lines}\n    return {method_info_var};")
+    }
 
-        template.write(writer)?;
+    /// Writes the out of line `methodInfo()` definition for the type's `.cpp` source file
+    pub fn write_source(&self, writer: &mut super::writer::CppWriter) -> color_eyre::Result<()> {
+        let utils_namespace = &self.utils_namespace;
+        let method_ptr = self.method_ptr();
 
         writeln!(
             writer,
-            "
-struct CORDL_HIDDEN ::il2cpp_utils::il2cpp_type_check::MetadataGetter<static_cast<{ret_type} ({f_ptr_prefix}*)({params_format})>(&{complete_type_name}::{cpp_method_name})> {{
-  constexpr static std::size_t size = 0x{size:x};
+            "const ::MethodInfo* {utils_namespace}::il2cpp_type_check::MetadataGetter<{method_ptr}>::methodInfo() {{
+  {}
+}}",
+            self.method_info_body()
+        )?;
+        Ok(())
+    }
+
+    /// Writes a `::cordl_internals::MethodHook` specialization for the method
+    pub fn write_hook(&self, writer: &mut super::writer::CppWriter) -> color_eyre::Result<()> {
+        let complete_type_name = &self.declaring_type_name;
+        let ret_type = &self.ret_ty;
+        let addr = &self.method_data.addrs;
+        let utils_namespace = &self.utils_namespace;
+        let method_ptr = self.method_ptr();
+
+        // native functions take the instance first and the MethodInfo last
+        let hook_params = self
+            .instance
+            .then(|| format!("{complete_type_name}*"))
+            .into_iter()
+            .chain(CppParam::params_types(&self.params).cloned())
+            .chain(["::MethodInfo const*".to_string()])
+            .join(", ");
+
+        writeln!(
+            writer,
+            "// Hook for method: {}.{}
+template <>
+struct CORDL_HIDDEN ::cordl_internals::MethodHook<{method_ptr}> {{
+  using hook_t = {ret_type} (*)({hook_params});
   constexpr static std::size_t addrs = 0x{addr:x};
 
   inline static const ::MethodInfo* methodInfo() {{
-    {method_info_lines}
-    return {method_info_var};
+    return {utils_namespace}::il2cpp_type_check::MetadataGetter<{method_ptr}>::methodInfo();
   }}
-}};"
+}};",
+            self.declaring_type_name, self.method_name
         )?;
         Ok(())
     }
@@ -2,13 +2,17 @@ use std::collections::{HashMap, HashSet};
 
 use brocolib::{
     global_metadata::{Il2CppTypeDefinition, MethodIndex, TypeDefinitionIndex},
-    runtime_metadata::Il2CppType,
+    runtime_metadata::{Il2CppType, TypeData},
 };
 use itertools::Itertools;
+use serde::Deserialize;
 
 use crate::data::name_components::NameComponents;
 
-use super::{context_collection::CppContextCollection, cpp_type::CppType};
+use super::{
+    context_collection::CppContextCollection,
+    cpp_type::{CppType, CppTypeRequirements},
+};
 
 pub struct MethodCalculations {
     pub estimated_size: usize,
@@ -34,7 +38,8 @@ impl<'a> TypeDefinitionPair<'a> {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TypeUsage {
     // Method usage
     Parameter,
@@ -50,6 +55,7 @@ pub enum TypeUsage {
 }
 
 pub type TypeHandlerFn = Box<dyn Fn(&mut CppType)>;
+/// The requirements are those of the type using the name, for includes the new name needs
 pub type TypeResolveHandlerFn = Box<
     dyn Fn(
         NameComponents,
@@ -58,6 +64,7 @@ pub type TypeResolveHandlerFn = Box<
         &Metadata,
         &Il2CppType,
         TypeUsage,
+        &mut CppTypeRequirements,
     ) -> NameComponents,
 >;
 pub type Il2cppNamespace<'a> = &'a str;
@@ -79,6 +86,8 @@ pub struct Metadata<'a> {
     //
     pub custom_type_handler: HashMap<TypeDefinitionIndex, TypeHandlerFn>,
     pub custom_type_resolve_handler: Vec<TypeResolveHandlerFn>,
+    /// C++ names to use instead of the metadata name, applied when the type is made
+    pub custom_type_names: HashMap<TypeDefinitionIndex, String>,
     pub name_to_tdi: HashMap<Il2cppFullName<'a>, TypeDefinitionIndex>,
     pub blacklisted_types: HashSet<TypeDefinitionIndex>,
 
@@ -90,6 +99,91 @@ pub struct Metadata<'a> {
 }
 
 impl<'a> Metadata<'a> {
+    pub fn new(metadata: &'a brocolib::Metadata<'a, 'a>) -> Self {
+        Metadata {
+            metadata,
+            code_registration: &metadata.runtime_metadata.code_registration,
+            metadata_registration: &metadata.runtime_metadata.metadata_registration,
+            method_calculations: Default::default(),
+            parent_to_child_map: Default::default(),
+            child_to_parent_map: Default::default(),
+            custom_type_handler: Default::default(),
+            custom_type_resolve_handler: Default::default(),
+            custom_type_names: Default::default(),
+            name_to_tdi: Default::default(),
+            blacklisted_types: Default::default(),
+            pointer_size: PointerSize::Bytes8,
+            // For most il2cpp versions
+            packing_field_offset: 7,
+            size_is_default_offset: 12,
+            specified_packing_field_offset: 13,
+            packing_is_default_offset: 11,
+        }
+    }
+
+    /// Registers a handler that runs after the type is filled.
+    /// Handlers already registered for the type run first
+    pub fn register_type_handler(&mut self, tdi: TypeDefinitionIndex, handler: TypeHandlerFn) {
+        let handler: TypeHandlerFn = match self.custom_type_handler.remove(&tdi) {
+            Some(existing) => Box::new(move |cpp_type: &mut CppType| {
+                existing(cpp_type);
+                handler(cpp_type);
+            }),
+            None => handler,
+        };
+
+        self.custom_type_handler.insert(tdi, handler);
+    }
+
+    /// Registers a handler that runs after every type name is resolved
+    pub fn register_type_resolve_handler(&mut self, handler: TypeResolveHandlerFn) {
+        self.custom_type_resolve_handler.push(handler);
+    }
+
+    /// Looks up a type by namespace and name, requires [`Metadata::parse`] to have been called
+    pub fn find_tdi(&self, namespace: &str, name: &str) -> Option<TypeDefinitionIndex> {
+        self.name_to_tdi
+            .get(&Il2cppFullName(namespace, name))
+            .copied()
+    }
+
+    /// Looks up a type by its full name, e.g. `System.Collections.Generic.List`1`
+    pub fn find_tdi_by_full_name(&self, full_name: &str) -> Option<TypeDefinitionIndex> {
+        self.metadata
+            .global_metadata
+            .type_definitions
+            .as_vec()
+            .iter()
+            .position(|t| t.full_name(self.metadata, false) == full_name)
+            .map(|tdi| TypeDefinitionIndex::new(tdi as u32))
+    }
+
+    /// The type definition of a type, or of its generic definition for generic instantiations
+    pub fn type_tdi(&self, ty: &Il2CppType) -> Option<TypeDefinitionIndex> {
+        match ty.data {
+            TypeData::TypeDefinitionIndex(tdi) => Some(tdi),
+            TypeData::GenericClassIndex(gen_class_idx) => {
+                let gen_class = &self.metadata_registration.generic_classes[gen_class_idx];
+
+                match self.metadata_registration.types[gen_class.type_index].data {
+                    TypeData::TypeDefinitionIndex(tdi) => Some(tdi),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Blacklists the type with the full name, returns false if no such type exists
+    pub fn blacklist_type(&mut self, full_name: &str) -> bool {
+        let Some(tdi) = self.find_tdi_by_full_name(full_name) else {
+            return false;
+        };
+
+        self.blacklisted_types.insert(tdi);
+        true
+    }
+
     /// Returns the size of the base object.
     /// To be used for boxing/unboxing and various offset computations.
     pub fn object_size(&self) -> u8 {
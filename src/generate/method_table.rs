@@ -0,0 +1,106 @@
+//! The central `MethodInfo` table used when [`GenerationConfig::method_table`] is enabled.
+//!
+//! Methods are indexed by their metadata method index, which is stable for a given
+//! global-metadata.dat. Instead of a guarded static and an inlined `FindMethod` per method,
+//! each call site looks its entry up through `::cordl_internals::method_table_get`,
+//! which resolves it on first use.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use color_eyre::eyre::Result;
+use itertools::Itertools;
+use log::info;
+
+use super::{
+    config::GenerationConfig, context_collection::CppContextCollection, cpp_type::CppType,
+    metadata::Metadata,
+};
+
+pub const METHOD_TABLE_PASS: &str = "method_table";
+
+/// Included by `method-table.hpp`, which only defines the table if this header exists
+pub const METHOD_TABLE_SIZE_HEADER: &str = "method-table-size.hpp";
+
+/// Writes the table size header and a report of how many methods use the table
+pub fn write_method_table(
+    collection: &CppContextCollection,
+    metadata: &Metadata,
+    config: &GenerationConfig,
+    report_file: &Path,
+) -> Result<()> {
+    let table_size = metadata.metadata.global_metadata.methods.as_vec().len();
+
+    let mut size_header = File::create(config.dst_internals_path.join(METHOD_TABLE_SIZE_HEADER))?;
+    writeln!(size_header, "#pragma once")?;
+    writeln!(size_header, "#define CORDL_METHOD_TABLE_SIZE {table_size}")?;
+
+    let types = collection
+        .get()
+        .values()
+        .flat_map(|c| c.typedef_types.values())
+        .flat_map(|t| {
+            t.nested_types_flattened()
+                .into_values()
+                .chain([t])
+                .collect_vec()
+        })
+        .collect_vec();
+
+    let entries = types
+        .iter()
+        .flat_map(|t| &t.method_table_entries)
+        .unique()
+        .count();
+    let types_using_table = types
+        .iter()
+        .filter(|t| !t.method_table_entries.is_empty())
+        .count();
+
+    info!("{entries} methods are resolved through the method table");
+
+    write_method_table_report(report_file, &types, entries, types_using_table, table_size)
+}
+
+fn write_method_table_report(
+    report_file: &Path,
+    types: &[&CppType],
+    entries: usize,
+    types_using_table: usize,
+    table_size: usize,
+) -> Result<()> {
+    let table_bytes = table_size * std::mem::size_of::<u64>();
+
+    let mut writer = BufWriter::new(File::create(report_file)?);
+
+    writeln!(writer, "Method table stats")?;
+    writeln!(writer, "methods using the table: {entries}")?;
+    writeln!(
+        writer,
+        "types using the table: {types_using_table}/{}",
+        types.len()
+    )?;
+    writeln!(
+        writer,
+        "table: {table_size} entries, 0x{table_bytes:x} bytes of zero initialized .bss"
+    )?;
+    writeln!(writer, "guarded statics replaced: {entries}")?;
+    writeln!(writer)?;
+    writeln!(
+        writer,
+        "The .bss table does not add to the .so file size, the removed guards and resolution code do."
+    )?;
+    writeln!(
+        writer,
+        "Generic types and methods keep their guarded statics, as instantiations share a method index."
+    )?;
+    writeln!(
+        writer,
+        "To measure the actual saving, compare the stripped .so sizes of builds with and without --method-table."
+    )?;
+
+    Ok(())
+}
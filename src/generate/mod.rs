@@ -6,9 +6,16 @@ pub mod cpp_type_tag;
 pub mod cs_context_collection;
 pub mod cs_fields;
 pub mod cs_type;
+pub mod failures;
+pub mod fingerprint;
+pub mod layout_check;
 pub mod members;
 pub mod members_serialize;
 pub mod metadata;
+pub mod method_table;
 pub mod offsets;
+pub mod passes;
+pub mod runtime_profile;
 pub mod type_extensions;
+pub mod verify;
 pub mod writer;
@@ -3,7 +3,7 @@ use core::mem;
 use crate::generate::metadata::Metadata;
 use crate::generate::metadata::PointerSize;
 use crate::generate::type_extensions::TypeDefinitionExtensions;
-use crate::TypeDefinitionIndex;
+use brocolib::global_metadata::TypeDefinitionIndex;
 
 use brocolib::global_metadata::Il2CppTypeDefinition;
 use brocolib::runtime_metadata::Il2CppTypeDefinitionSizes;
@@ -214,6 +214,11 @@ fn size_is_default(bitfield: u32, size_is_default_offset: u8) -> bool {
     ((bitfield >> (size_is_default_offset - 1)) & 0x1) != 0
 }
 
+/// Whether the type has an explicit `[StructLayout(Size = N)]`, which replaces the calculated instance size
+pub fn has_explicit_size(metadata: &Metadata<'_>, ty_def: &Il2CppTypeDefinition) -> bool {
+    !size_is_default(ty_def.bitfield, metadata.size_is_default_offset)
+}
+
 fn get_size(
     metadata: &Metadata<'_>,
     tdi: TypeDefinitionIndex,
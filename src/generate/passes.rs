@@ -0,0 +1,215 @@
+use std::{collections::HashSet, path::PathBuf, time};
+
+use color_eyre::eyre::{bail, Result};
+use itertools::Itertools;
+use log::{info, trace};
+
+use crate::handlers::{
+    collections::{add_collection_requirements, COLLECTION_REQUIREMENTS_PASS},
+    comment_omit::remove_coments,
+    register_default_handlers,
+};
+
+use super::{
+    config::GenerationConfig, context_collection::CppContextCollection,
+    layout_check::quarantine_layout_mismatches, metadata::Metadata,
+};
+
+///
+/// A named step of generation
+///
+pub trait Pass {
+    fn name(&self) -> &str;
+
+    fn run(
+        &self,
+        collection: &mut CppContextCollection,
+        metadata: &mut Metadata,
+        config: &GenerationConfig,
+    ) -> Result<()>;
+}
+
+pub type PassFn =
+    Box<dyn Fn(&mut CppContextCollection, &mut Metadata, &GenerationConfig) -> Result<()>>;
+
+/// A pass backed by a closure
+pub struct FnPass {
+    pub name: String,
+    pub func: PassFn,
+}
+
+impl FnPass {
+    pub fn new(
+        name: impl Into<String>,
+        func: impl Fn(&mut CppContextCollection, &mut Metadata, &GenerationConfig) -> Result<()>
+            + 'static,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            func: Box::new(func),
+        }
+    }
+}
+
+impl Pass for FnPass {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn run(
+        &self,
+        collection: &mut CppContextCollection,
+        metadata: &mut Metadata,
+        config: &GenerationConfig,
+    ) -> Result<()> {
+        (self.func)(collection, metadata, config)
+    }
+}
+
+pub const CHECK_LAYOUTS_PASS: &str = "check_layouts";
+pub const MAKE_TYPES_PASS: &str = "make_types";
+pub const MAKE_NESTED_TYPES_PASS: &str = "make_nested_types";
+pub const GENERIC_METHODS_PASS: &str = "generic_methods";
+pub const REGISTER_HANDLERS_PASS: &str = "register_handlers";
+pub const FILL_TYPES_PASS: &str = "fill_types";
+pub const REMOVE_COMMENTS_PASS: &str = "remove_comments";
+pub const WRITE_PASS: &str = "write";
+
+#[derive(Default)]
+pub struct PassManager {
+    passes: Vec<Box<dyn Pass>>,
+    disabled: HashSet<String>,
+}
+
+impl PassManager {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    ///
+    /// The built-in passes in the order the CLI runs them.
+    /// The generic methods and remove comments passes are opt-in
+    ///
+    pub fn with_default_passes(
+        layout_report: PathBuf,
+        generic_methods: bool,
+        remove_comments: bool,
+    ) -> Self {
+        let mut manager = Self::new();
+
+        manager.add(FnPass::new(CHECK_LAYOUTS_PASS, move |_, metadata, _| {
+            // Types whose computed layout disagrees with the runtime metadata
+            // would otherwise fail to compile, usually due to il2cpp bugs
+            quarantine_layout_mismatches(metadata, &layout_report)
+        }));
+        manager.add(FnPass::new(
+            MAKE_TYPES_PASS,
+            |collection, metadata, config| {
+                collection.make_root_types(metadata, config);
+                Ok(())
+            },
+        ));
+        manager.add(FnPass::new(
+            MAKE_NESTED_TYPES_PASS,
+            |collection, metadata, config| {
+                collection.make_nested_types(metadata, config);
+                Ok(())
+            },
+        ));
+        if generic_methods {
+            manager.add(FnPass::new(
+                GENERIC_METHODS_PASS,
+                |collection, metadata, config| {
+                    collection.fill_generic_method_insts(metadata, config);
+                    Ok(())
+                },
+            ));
+        }
+        manager.add(FnPass::new(
+            REGISTER_HANDLERS_PASS,
+            |_, metadata, config| register_default_handlers(metadata, config),
+        ));
+        manager.add(FnPass::new(
+            FILL_TYPES_PASS,
+            |collection, metadata, config| {
+                collection.fill_all(metadata, config);
+                Ok(())
+            },
+        ));
+        manager.add(FnPass::new(
+            COLLECTION_REQUIREMENTS_PASS,
+            |collection, metadata, _| add_collection_requirements(collection, metadata),
+        ));
+        if remove_comments {
+            manager.add(FnPass::new(REMOVE_COMMENTS_PASS, |collection, _, _| {
+                remove_coments(collection)
+            }));
+        }
+        manager.add(FnPass::new(WRITE_PASS, |collection, _, config| {
+            collection.write_all(config)?;
+            collection.write_namespace_headers(config)
+        }));
+
+        manager
+    }
+
+    pub fn pass_names(&self) -> impl Iterator<Item = &str> {
+        self.passes.iter().map(|p| p.name())
+    }
+
+    fn position(&self, name: &str) -> Result<usize> {
+        match self.passes.iter().position(|p| p.name() == name) {
+            Some(i) => Ok(i),
+            None => bail!(
+                "No pass named {name}, available passes: {}",
+                self.pass_names().join(", ")
+            ),
+        }
+    }
+
+    /// Add a pass to the end of the pipeline
+    pub fn add(&mut self, pass: impl Pass + 'static) {
+        self.passes.push(Box::new(pass));
+    }
+
+    pub fn insert_before(&mut self, name: &str, pass: impl Pass + 'static) -> Result<()> {
+        let i = self.position(name)?;
+        self.passes.insert(i, Box::new(pass));
+        Ok(())
+    }
+
+    pub fn insert_after(&mut self, name: &str, pass: impl Pass + 'static) -> Result<()> {
+        let i = self.position(name)?;
+        self.passes.insert(i + 1, Box::new(pass));
+        Ok(())
+    }
+
+    /// Skip the pass when running, the pass must exist
+    pub fn disable(&mut self, name: &str) -> Result<()> {
+        self.position(name)?;
+        self.disabled.insert(name.to_string());
+        Ok(())
+    }
+
+    pub fn run(
+        &self,
+        collection: &mut CppContextCollection,
+        metadata: &mut Metadata,
+        config: &GenerationConfig,
+    ) -> Result<()> {
+        for pass in &self.passes {
+            let name = pass.name();
+            if self.disabled.contains(name) {
+                info!("Skipping disabled pass {name}");
+                continue;
+            }
+
+            info!("Running pass {name}");
+            let t = time::Instant::now();
+            pass.run(collection, metadata, config)?;
+            trace!("Pass {name} finished in {}ms", t.elapsed().as_millis());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,122 @@
+use std::{
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::eyre::{Context, Result};
+use serde::Deserialize;
+
+pub const BEATSABER_HOOK_PROFILE: &str = "beatsaber-hook";
+
+/// Headers of the runtime library included by generated types
+#[derive(Debug, Clone, Deserialize)]
+pub struct RuntimeIncludes {
+    /// Included first by every generated header
+    pub typedefs: PathBuf,
+    pub base_wrapper: PathBuf,
+    pub string: PathBuf,
+    pub array: PathBuf,
+    pub byref: PathBuf,
+    pub enum_type: PathBuf,
+    pub value_type: PathBuf,
+}
+
+///
+/// The runtime library the generated code is written against.
+/// The bundled cordl_internals are written for beatsaber-hook,
+/// other runtimes must provide the same helpers under their `utils_namespace`
+///
+#[derive(Debug, Clone, Deserialize)]
+pub struct RuntimeProfile {
+    pub name: String,
+    pub includes: RuntimeIncludes,
+
+    /// Namespace of `FindMethod`, `Box`, `NewSpecific`, `MakeDelegate`, `il2cpp_type_check` etc.
+    pub utils_namespace: String,
+
+    pub string_type: String,
+    /// Template of single dimension arrays
+    pub array_type: String,
+    pub byref_type: String,
+    pub byref_const_type: String,
+
+    pub reference_wrapper_instance: String,
+    pub value_wrapper_type: String,
+    pub enum_wrapper_type: String,
+    pub enum_ptr_type: String,
+    pub vt_ptr_type: String,
+
+    /// Template wrapping references to UnityEngine.Object types, if any
+    #[serde(default)]
+    pub unity_object_wrapper: Option<String>,
+    /// Template replacing System.Nullable<T> in fields, parameters and return types, if any
+    #[serde(default)]
+    pub nullable_wrapper: Option<String>,
+}
+
+impl RuntimeProfile {
+    pub fn beatsaber_hook() -> Self {
+        Self {
+            name: BEATSABER_HOOK_PROFILE.to_string(),
+            includes: RuntimeIncludes {
+                typedefs: "beatsaber-hook/shared/utils/typedefs.h".into(),
+                base_wrapper: "beatsaber-hook/shared/utils/base-wrapper-type.hpp".into(),
+                string: "beatsaber-hook/shared/utils/typedefs-string.hpp".into(),
+                array: "beatsaber-hook/shared/utils/typedefs-array.hpp".into(),
+                byref: "beatsaber-hook/shared/utils/byref.hpp".into(),
+                enum_type: "beatsaber-hook/shared/utils/enum-type.hpp".into(),
+                value_type: "beatsaber-hook/shared/utils/value-type.hpp".into(),
+            },
+            utils_namespace: "::il2cpp_utils".to_string(),
+            string_type: "::StringW".to_string(),
+            array_type: "ArrayW".to_string(),
+            byref_type: "ByRef".to_string(),
+            byref_const_type: "ByRefConst".to_string(),
+            reference_wrapper_instance: "::bs_hook::Il2CppWrapperType::instance".to_string(),
+            value_wrapper_type: "::bs_hook::ValueType".to_string(),
+            enum_wrapper_type: "::bs_hook::EnumType".to_string(),
+            enum_ptr_type: "::bs_hook::EnumPtr".to_string(),
+            vt_ptr_type: "::bs_hook::VTPtr".to_string(),
+            unity_object_wrapper: Some("UnityW".to_string()),
+            nullable_wrapper: Some("NullableW".to_string()),
+        }
+    }
+
+    /// The profiles shipped with cordl
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            BEATSABER_HOOK_PROFILE => Some(Self::beatsaber_hook()),
+            _ => None,
+        }
+    }
+
+    /// Reads a profile from a JSON file
+    pub fn load(file: &Path) -> Result<Self> {
+        let reader = BufReader::new(File::open(file).with_context(|| format!("{file:?}"))?);
+
+        serde_json::from_reader(reader).with_context(|| format!("Parsing runtime profile {file:?}"))
+    }
+
+    /// A builtin profile name or the path to a profile file
+    pub fn from_name_or_file(profile: &str) -> Result<Self> {
+        match Self::builtin(profile) {
+            Some(profile) => Ok(profile),
+            None => Self::load(Path::new(profile)),
+        }
+    }
+
+    pub fn type_check_namespace(&self) -> String {
+        format!("{}::il2cpp_type_check", self.utils_namespace)
+    }
+
+    pub fn reference_type_constraint(&self) -> String {
+        format!("{}::il2cpp_reference_type", self.utils_namespace)
+    }
+}
+
+impl Default for RuntimeProfile {
+    fn default() -> Self {
+        Self::beatsaber_hook()
+    }
+}
@@ -23,6 +23,7 @@ pub const TYPE_ATTRIBUTE_SPECIAL_NAME: u32 = 0x00000400;
 pub const FIELD_ATTRIBUTE_PUBLIC: u16 = 0x0006;
 pub const FIELD_ATTRIBUTE_PRIVATE: u16 = 0x0001;
 pub const FIELD_ATTRIBUTE_STATIC: u16 = 0x0010;
+pub const FIELD_ATTRIBUTE_INIT_ONLY: u16 = 0x0020;
 pub const FIELD_ATTRIBUTE_LITERAL: u16 = 0x0040;
 
 pub const METHOD_ATTRIBUTE_PUBLIC: u16 = 0x0006;
@@ -96,6 +97,7 @@ impl ParameterDefinitionExtensions for Il2CppType {
 pub trait TypeExtentions {
     fn is_static(&self) -> bool;
     fn is_constant(&self) -> bool;
+    fn is_init_only(&self) -> bool;
     fn is_byref(&self) -> bool;
 
     fn fill_generic_inst<'a>(
@@ -115,6 +117,11 @@ impl TypeExtentions for Il2CppType {
         (self.attrs & FIELD_ATTRIBUTE_LITERAL) != 0
     }
 
+    // FIELD_ATTRIBUTE_INIT_ONLY, readonly fields
+    fn is_init_only(&self) -> bool {
+        (self.attrs & FIELD_ATTRIBUTE_INIT_ONLY) != 0
+    }
+
     fn is_byref(&self) -> bool {
         self.byref
     }
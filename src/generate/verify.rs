@@ -0,0 +1,137 @@
+//! Writes `cordl_verify.cpp`, which compares the generated layouts with the running game.
+//!
+//! The static asserts in the headers only check the generated structs against the sizes cordl computed.
+//! Calling `cordl_verify::verify_layouts` once at load instead compares those sizes and offsets
+//! with the live `Il2CppClass` of each type, so a libil2cpp that doesn't match the metadata
+//! shows up as a list of mismatches instead of memory corruption.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+use color_eyre::eyre::Result;
+use itertools::Itertools;
+use log::info;
+
+use super::{
+    config::GenerationConfig, context_collection::CppContextCollection, cpp_type::CppType,
+    cs_type::escape_cpp_literal, metadata::Metadata,
+};
+
+pub const VERIFY_PASS: &str = "verify";
+
+pub const VERIFY_SOURCE_FILE: &str = "cordl_verify.cpp";
+
+pub fn write_verify_source(
+    collection: &CppContextCollection,
+    metadata: &Metadata,
+    config: &GenerationConfig,
+) -> Result<()> {
+    // generic definitions have no layout of their own
+    let types = collection
+        .get()
+        .values()
+        .flat_map(|c| c.typedef_types.values())
+        .flat_map(|t| {
+            t.nested_types_flattened()
+                .into_values()
+                .chain([t])
+                .collect_vec()
+        })
+        .filter(|t| !t.is_stub && t.cpp_template.is_none())
+        .sorted_by_key(|t| t.self_tag.get_tdi().index())
+        .collect_vec();
+
+    let file = config.source_path.join(VERIFY_SOURCE_FILE);
+    info!("Writing layout checks of {} types to {file:?}", types.len());
+
+    std::fs::create_dir_all(&config.source_path)?;
+    let mut writer = BufWriter::new(File::create(file)?);
+
+    writeln!(
+        writer,
+        "// Generated by cordl, compares the generated layouts with the running game"
+    )?;
+    writeln!(writer, "#include \"cordl_internals/verify.hpp\"")?;
+    writeln!(
+        writer,
+        "#include \"{}\"",
+        config.runtime.includes.typedefs.display()
+    )?;
+    writeln!(writer)?;
+    writeln!(writer, "namespace {{")?;
+
+    write_field_table(&mut writer, &types)?;
+    write_type_table(&mut writer, &types, metadata)?;
+
+    writeln!(writer, "}} // end anonymous namespace")?;
+    writeln!(writer)?;
+    writeln!(writer, "#include \"cordl_internals/verify-impl.hpp\"")?;
+
+    Ok(())
+}
+
+fn write_field_table(writer: &mut impl Write, types: &[&CppType]) -> Result<()> {
+    let fields = types
+        .iter()
+        .flat_map(|t| &t.instance_field_offsets)
+        .collect_vec();
+
+    writeln!(
+        writer,
+        "constexpr std::array<::cordl_verify::FieldLayout, {}> fields{{{{",
+        fields.len()
+    )?;
+    for (name, offset) in fields {
+        writeln!(
+            writer,
+            "  {{\"{}\", 0x{offset:x}}},",
+            escape_cpp_literal(name)
+        )?;
+    }
+    writeln!(writer, "}}}};")?;
+
+    Ok(())
+}
+
+fn write_type_table(
+    writer: &mut impl Write,
+    types: &[&CppType],
+    metadata: &Metadata,
+) -> Result<()> {
+    writeln!(
+        writer,
+        "constexpr std::array<::cordl_verify::TypeLayout, {}> types{{{{",
+        types.len()
+    )?;
+
+    let mut fields_start = 0;
+    for t in types {
+        let tdi = t.self_tag.get_tdi();
+        let td = &metadata.metadata.global_metadata.type_definitions[tdi];
+
+        // interfaces have no instance size to compare
+        let instance_size = match t.is_interface {
+            true => None,
+            false => t.size_info.as_ref().map(|s| s.instance_size),
+        };
+        let fields_count = t.instance_field_offsets.len();
+
+        writeln!(
+            writer,
+            "  {{\"{}\", {}, {}, {}, 0x{:x}, {}, {fields_start}, {fields_count}}},",
+            escape_cpp_literal(&t.cpp_name_components.remove_pointer().combine_all()),
+            tdi.index(),
+            t.is_value_type || t.is_enum_type,
+            instance_size.is_some(),
+            instance_size.unwrap_or_default(),
+            td.method_count,
+        )?;
+
+        fields_start += fields_count;
+    }
+    writeln!(writer, "}}}};")?;
+
+    Ok(())
+}
@@ -0,0 +1,333 @@
+//! `begin()`/`end()` adapters for types implementing the generic collection interfaces,
+//! so they can be iterated with range based for loops.
+//!
+//! Lists with an indexer (`IList<T>`, `IReadOnlyList<T>`) get random access iterators,
+//! everything else implementing `IEnumerable<T>` is walked through its enumerator.
+//!
+//! The interfaces the adapters name are forward declared and included by [`COLLECTION_REQUIREMENTS_PASS`],
+//! which runs once all contexts are filled.
+
+use std::{collections::HashMap, sync::Arc};
+
+use brocolib::global_metadata::TypeDefinitionIndex;
+use color_eyre::Result;
+use itertools::Itertools;
+use log::info;
+
+use crate::{
+    data::name_components::NameComponents,
+    generate::{
+        context_collection::CppContextCollection,
+        cpp_type::CppType,
+        cpp_type_tag::CppTypeTag,
+        members::{
+            CppForwardDeclare, CppInclude, CppLine, CppMember, CppMethodDecl, CppMethodImpl,
+        },
+        metadata::Metadata,
+    },
+};
+
+pub const COLLECTION_REQUIREMENTS_PASS: &str = "collection_requirements";
+
+const COLLECTIONS_NAMESPACE: &str = "System.Collections";
+const GENERIC_COLLECTIONS_NAMESPACE: &str = "System.Collections.Generic";
+const ITERABLE_INTERFACES: [&str; 3] = ["IEnumerable`1", "IList`1", "IReadOnlyList`1"];
+
+enum IterationKind {
+    /// list interface, its count interface
+    Indexed(NameComponents, NameComponents),
+    /// enumerable interface
+    Enumerated(NameComponents),
+}
+
+pub fn register_collections(metadata: &mut Metadata) -> Result<()> {
+    info!("Registering collection iteration handler!");
+
+    let iterable_tdis = ITERABLE_INTERFACES
+        .iter()
+        .filter_map(|name| metadata.find_tdi(GENERIC_COLLECTIONS_NAMESPACE, name))
+        .collect_vec();
+
+    let type_defs = &metadata.metadata.global_metadata.type_definitions;
+    let tdis = type_defs
+        .as_vec()
+        .iter()
+        .enumerate()
+        .filter(|(i, td)| {
+            let tdi = TypeDefinitionIndex::new(*i as u32);
+
+            iterable_tdis.contains(&tdi)
+                || td
+                    .interfaces(metadata.metadata)
+                    .iter()
+                    .any(|&interface_index| {
+                        let ty = &metadata.metadata_registration.types[interface_index as usize];
+                        metadata
+                            .type_tdi(ty)
+                            .is_some_and(|tdi| iterable_tdis.contains(&tdi))
+                    })
+        })
+        .map(|(i, _)| TypeDefinitionIndex::new(i as u32))
+        .collect_vec();
+
+    info!("Adding iteration adapters to {} types", tdis.len());
+    for tdi in tdis {
+        metadata.register_type_handler(tdi, Box::new(collection_handler));
+    }
+
+    Ok(())
+}
+
+/// Forward declares the interfaces named by the adapters in the def headers and includes them in the impl headers
+pub fn add_collection_requirements(
+    collection: &mut CppContextCollection,
+    metadata: &Metadata,
+) -> Result<()> {
+    // by il2cpp name: the context the interface lives in, its forward declare and includes
+    let interfaces: HashMap<_, _> = adapter_interface_names()
+        .into_iter()
+        .filter_map(|(namespace, name)| {
+            let tag: CppTypeTag = metadata.find_tdi(namespace, name)?.into();
+            let context = collection.get_context(tag)?;
+            let interface = collection.get_cpp_type(tag)?;
+
+            let requirement = (
+                collection.get_context_root_tag(tag),
+                CppForwardDeclare::from_cpp_type(interface),
+                CppInclude::new_context_typedef(context),
+                CppInclude::new_context_typeimpl(context),
+            );
+            Some((name, requirement))
+        })
+        .collect();
+
+    info!("Adding collection interface requirements");
+    for (context_tag, context) in collection.get_mut() {
+        for cpp_type in context.typedef_types.values_mut() {
+            add_type_requirements(cpp_type, *context_tag, &interfaces);
+        }
+    }
+
+    Ok(())
+}
+
+fn add_type_requirements(
+    cpp_type: &mut CppType,
+    context_tag: CppTypeTag,
+    interfaces: &HashMap<&str, (CppTypeTag, CppForwardDeclare, CppInclude, CppInclude)>,
+) {
+    for nested in cpp_type.nested_types.values_mut() {
+        add_type_requirements(nested, context_tag, interfaces);
+    }
+
+    if !has_iteration_adapters(cpp_type) {
+        return;
+    }
+    let Some(kind) = adapter_kind(cpp_type) else {
+        return;
+    };
+
+    for name in used_interfaces(&kind) {
+        let Some((interface_context, fd, typedef_incl, typeimpl_incl)) = interfaces.get(name)
+        else {
+            continue;
+        };
+
+        // Skip including the context if we're already in it
+        if *interface_context == context_tag {
+            continue;
+        }
+
+        cpp_type
+            .requirements
+            .add_forward_declare((fd.clone(), typedef_incl.clone()));
+        cpp_type
+            .requirements
+            .add_impl_include(None, typeimpl_incl.clone());
+    }
+}
+
+/// Namespace and il2cpp name of every interface an adapter can name
+fn adapter_interface_names() -> Vec<(&'static str, &'static str)> {
+    let generic = [
+        "IEnumerable`1",
+        "IEnumerator`1",
+        "IList`1",
+        "ICollection`1",
+        "IReadOnlyList`1",
+        "IReadOnlyCollection`1",
+    ];
+
+    generic
+        .into_iter()
+        .map(|name| (GENERIC_COLLECTIONS_NAMESPACE, name))
+        .chain([(COLLECTIONS_NAMESPACE, "IEnumerator")])
+        .collect_vec()
+}
+
+/// The il2cpp names of the interfaces the adapters of `kind` name
+fn used_interfaces(kind: &IterationKind) -> Vec<&'static str> {
+    match kind {
+        IterationKind::Indexed(list, _) if list.name == "IList_1" => {
+            vec!["IList`1", "ICollection`1"]
+        }
+        IterationKind::Indexed(_, _) => vec!["IReadOnlyList`1", "IReadOnlyCollection`1"],
+        IterationKind::Enumerated(_) => vec!["IEnumerable`1", "IEnumerator`1", "IEnumerator"],
+    }
+}
+
+/// Whether [`collection_handler`] added the adapters to the type
+fn has_iteration_adapters(cpp_type: &CppType) -> bool {
+    cpp_type.declarations.iter().any(|d| match d.as_ref() {
+        CppMember::MethodDecl(m) => m.cpp_name == "begin" && m.instance && m.parameters.is_empty(),
+        _ => false,
+    })
+}
+
+fn adapter_kind(cpp_type: &CppType) -> Option<IterationKind> {
+    // the adapters reinterpret `this` as the interface, which needs an object
+    if cpp_type.is_value_type || cpp_type.is_enum_type {
+        return None;
+    }
+
+    iteration_kind(cpp_type)
+}
+
+fn collection_handler(cpp_type: &mut CppType) {
+    let Some(kind) = adapter_kind(cpp_type) else {
+        return;
+    };
+
+    let (iterator, begin, end) = match kind {
+        IterationKind::Indexed(list, count) => {
+            let element = element_type(&list);
+            let list = list.combine_all();
+            let count = count.combine_all();
+            let iterator = format!("::cordl_internals::IndexIterator<{list}, {element}>");
+
+            (
+                iterator.clone(),
+                format!("return {iterator}(reinterpret_cast<{list}*>(this), 0);"),
+                format!(
+                    "return {iterator}(reinterpret_cast<{list}*>(this), ::cordl_internals::collection_count<{count}>(this));"
+                ),
+            )
+        }
+        IterationKind::Enumerated(enumerable) => {
+            let element = element_type(&enumerable);
+            let enumerator = sibling_interface(&enumerable, "IEnumerator_1").combine_all();
+            let enumerable = enumerable.combine_all();
+            let iterator = format!(
+                "::cordl_internals::EnumeratorIterator<{enumerator}, ::System::Collections::IEnumerator, {element}>"
+            );
+
+            (
+                iterator.clone(),
+                format!(
+                    "return {iterator}(::cordl_internals::collection_enumerator<{enumerable}>(this));"
+                ),
+                format!("return {iterator}();"),
+            )
+        }
+    };
+
+    add_iteration_method(cpp_type, "begin", &iterator, begin);
+    add_iteration_method(cpp_type, "end", &iterator, end);
+}
+
+/// Picks the most capable collection interface of the type, or the type itself for interfaces
+fn iteration_kind(cpp_type: &CppType) -> Option<IterationKind> {
+    let own_name = cpp_type.cpp_name_components.remove_pointer();
+    let candidates = cpp_type
+        .is_interface
+        .then_some(&own_name)
+        .into_iter()
+        .chain(cpp_type.interfaces.iter())
+        .collect_vec();
+
+    let find = |name: &str| {
+        candidates
+            .iter()
+            .find(|c| is_generic_collection(c, name))
+            .map(|c| (*c).clone())
+    };
+
+    let indexed = [
+        ("IList_1", "ICollection_1"),
+        ("IReadOnlyList_1", "IReadOnlyCollection_1"),
+    ];
+    for (list_name, count_name) in indexed {
+        if let Some(list) = find(list_name) {
+            let count = sibling_interface(&list, count_name);
+            return Some(IterationKind::Indexed(list, count));
+        }
+    }
+
+    find("IEnumerable_1").map(IterationKind::Enumerated)
+}
+
+fn is_generic_collection(name: &NameComponents, expected: &str) -> bool {
+    name.name == expected
+        && name.declaring_types.is_none()
+        && name
+            .namespace
+            .as_deref()
+            .is_some_and(|ns| ns.trim_start_matches("::") == "System::Collections::Generic")
+        && name.generics.as_ref().is_some_and(|g| g.len() == 1)
+}
+
+/// Another interface of the same namespace with the same generic args, e.g. `ICollection_1<T>` for `IList_1<T>`
+fn sibling_interface(interface: &NameComponents, name: &str) -> NameComponents {
+    NameComponents {
+        name: name.to_string(),
+        ..interface.remove_pointer()
+    }
+}
+
+fn element_type(interface: &NameComponents) -> String {
+    interface.generics.as_ref().unwrap()[0].clone()
+}
+
+fn add_iteration_method(cpp_type: &mut CppType, name: &str, iterator: &str, body: String) {
+    let method_decl = CppMethodDecl {
+        cpp_name: name.to_string(),
+        return_type: iterator.to_string(),
+        parameters: vec![],
+        instance: true,
+        template: None,
+        suffix_modifiers: vec![],
+        prefix_modifiers: vec![],
+        is_virtual: false,
+        is_constexpr: false,
+        is_const: false,
+        is_no_except: false,
+        is_implicit_operator: false,
+        is_explicit_operator: false,
+        is_inline: true,
+        brief: Some(format!(
+            "Range based for loop adapter, `{name}` of the collection"
+        )),
+        body: None,
+    };
+
+    let declaring_type_template = cpp_type
+        .cpp_template
+        .as_ref()
+        .is_some_and(|t| !t.names.is_empty())
+        .then(|| cpp_type.cpp_template.clone())
+        .flatten();
+
+    let method_impl = CppMethodImpl {
+        body: vec![Arc::new(CppLine::make(body))],
+        declaring_cpp_full_name: cpp_type.cpp_name_components.remove_pointer().combine_all(),
+        declaring_type_template,
+        ..method_decl.clone().into()
+    };
+
+    cpp_type
+        .declarations
+        .push(CppMember::MethodDecl(method_decl).into());
+    cpp_type
+        .implementations
+        .push(CppMember::MethodImpl(method_impl).into());
+}
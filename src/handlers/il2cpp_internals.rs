@@ -1,17 +1,28 @@
+//! Conversions between cordl types and their libil2cpp struct equivalents,
+//! e.g. `System::Exception*` and `Il2CppException*`.
+//!
+//! Both describe the same object layout, which is proven by size and alignment asserts
+//! emitted next to the type. The libil2cpp structs come from the runtime's typedefs header.
+
 use color_eyre::Result;
 use log::{info, trace, warn};
 use std::{
     collections::HashMap,
+    rc::Rc,
     sync::{Arc, LazyLock},
 };
 
 use crate::generate::{
     cpp_type::CppType,
-    cs_type::VALUE_TYPE_WRAPPER_SIZE,
-    members::{CppConstructorDecl, CppLine, CppMember, CppMethodDecl, CppParam},
-    metadata::{Il2cppFullName, Metadata},
+    members::{
+        CppInclude, CppLine, CppMember, CppMethodDecl, CppNonMember, CppParam, CppStaticAssert,
+    },
+    metadata::Metadata,
 };
 
+/// The pass registering the equivalence handlers, only run when requested
+pub const IL2CPP_INTERNALS_PASS: &str = "il2cpp_internals";
+
 static EQUIVALENTS: LazyLock<HashMap<&str, &str>> = LazyLock::new(|| {
     HashMap::from([
         ("System.RuntimeType", "Il2CppReflectionRuntimeType"),
@@ -97,208 +108,187 @@ static EQUIVALENTS: LazyLock<HashMap<&str, &str>> = LazyLock::new(|| {
 pub fn register_il2cpp_types(metadata: &mut Metadata) -> Result<()> {
     info!("Registering il2cpp type handler!");
 
-    for (cordl_t, il2cpp_t) in EQUIVALENTS.iter() {
+    for (&cordl_t, &il2cpp_t) in EQUIVALENTS.iter() {
         info!("Registering il2cpp type handler {cordl_t} to {il2cpp_t}");
 
         let (cordl_t_ns, cordl_t_name) = cordl_t.rsplit_once('.').expect("No namespace?");
-        let il2cpp_name = Il2cppFullName(cordl_t_ns, cordl_t_name);
-
-        let cordl_tdi = metadata.name_to_tdi.get(&il2cpp_name);
-
-        match cordl_tdi {
-            Some(cordl_tdi) => {
-                metadata.custom_type_handler.insert(
-                    *cordl_tdi,
-                    Box::new(|cpp_type| il2cpp_alias_handler(cpp_type, cordl_t, il2cpp_t)),
-                );
-            }
-            None => {
-                warn!("Could not find TDI for {cordl_t}");
-            }
-        }
+        let Some(cordl_tdi) = metadata.find_tdi(cordl_t_ns, cordl_t_name) else {
+            warn!("Could not find TDI for {cordl_t}");
+            continue;
+        };
+
+        metadata.register_type_handler(
+            cordl_tdi,
+            Box::new(move |cpp_type: &mut CppType| {
+                il2cpp_alias_handler(cpp_type, cordl_t, il2cpp_t)
+            }),
+        );
     }
 
     Ok(())
 }
 
 fn il2cpp_alias_handler(cpp_type: &mut CppType, cordl_t: &str, il2cpp_t: &str) {
-    trace!("Replacing {cordl_t} for il2cpp il2cpp_t for type {il2cpp_t}");
+    trace!("Adding il2cpp conversions from {cordl_t} to {il2cpp_t}");
+
+    // the layout is only known for complete types
+    if cpp_type.cpp_template.is_some() {
+        warn!("Skipping il2cpp equivalent {il2cpp_t} of generic type {cordl_t}");
+        return;
+    }
 
-    // there is an il2cpp api il2cpp_t configured for this type,
-    // we should emit some conversion operators for that
+    let il2cpp_t = format!("::{il2cpp_t}");
 
     if cpp_type.is_value_type {
-        value_type_convert(cpp_type, il2cpp_t);
+        value_type_convert(cpp_type, &il2cpp_t);
     } else {
-        reference_type_convert(cpp_type, il2cpp_t);
+        reference_type_convert(cpp_type, &il2cpp_t);
     }
-}
 
-fn reference_type_convert(cpp_type: &mut CppType, il2cpp_t: &str) {
-    let cpp_name = cpp_type.cpp_name();
+    add_layout_asserts(cpp_type, &il2cpp_t);
+}
 
-    let operator_body = format!("return static_cast<{il2cpp_t}*>(this->convert());");
-    let conversion_operator = CppMethodDecl {
-        cpp_name: Default::default(),
+fn conversion_method(
+    cpp_name: &str,
+    return_type: String,
+    parameters: Vec<CppParam>,
+    body: String,
+) -> CppMethodDecl {
+    CppMethodDecl {
+        cpp_name: cpp_name.to_string(),
         instance: true,
-        return_type: format!("{il2cpp_t}*"),
+        return_type,
 
-        brief: Some(format!("Conversion into il2cpp il2cpp_t {il2cpp_t}")),
-        body: Some(vec![Arc::new(CppLine::make(operator_body))]), // TODO:
+        brief: None,
+        body: Some(vec![Arc::new(CppLine::make(body))]),
         is_const: false,
-        is_constexpr: true,
+        is_constexpr: false,
         is_virtual: false,
-        is_implicit_operator: true,
+        is_implicit_operator: false,
         is_explicit_operator: false,
 
         is_inline: true,
         is_no_except: true,
-        parameters: vec![],
+        parameters,
         prefix_modifiers: vec![],
         suffix_modifiers: vec![],
         template: None,
-    };
-
-    let const_operator_body = format!("return static_cast<{il2cpp_t} const*>(this->convert());");
-    let const_conversion_operator = CppMethodDecl {
-        cpp_name: Default::default(),
-        instance: true,
-        return_type: format!("{il2cpp_t} const*"),
+    }
+}
 
-        brief: Some(format!("Conversion into il2cpp il2cpp_t {il2cpp_t}")),
-        body: Some(vec![Arc::new(CppLine::make(const_operator_body))]), // TODO:
-        is_const: true,
-        is_constexpr: true,
-        is_virtual: false,
-        is_implicit_operator: true,
-        is_explicit_operator: false,
+fn reference_type_convert(cpp_type: &mut CppType, il2cpp_t: &str) {
+    let cpp_name = cpp_type.cpp_name().clone();
 
-        is_inline: true,
-        is_no_except: true,
-        parameters: vec![],
-        prefix_modifiers: vec![],
-        suffix_modifiers: vec![],
-        template: None,
+    // objects are only ever used through pointers, so the conversions are explicit methods
+    let to_il2cpp = CppMethodDecl {
+        brief: Some(format!(
+            "Reinterprets this object as the il2cpp struct {il2cpp_t}"
+        )),
+        ..conversion_method(
+            "to_il2cpp",
+            format!("{il2cpp_t}*"),
+            vec![],
+            format!("return reinterpret_cast<{il2cpp_t}*>(this);"),
+        )
     };
 
-    let il2cpp_t_constructor = CppConstructorDecl {
-        cpp_name: cpp_name.clone(),
-        parameters: vec![CppParam {
-            name: "il2cpp_ptr".to_string(),
-            modifiers: "".to_string(),
-            ty: format!("{il2cpp_t}*").to_string(),
-            def_value: None,
-        }],
-        template: None,
-        is_constexpr: true,
-        is_explicit: false,
-        is_default: false,
-        is_no_except: true,
-        is_delete: false,
-        is_protected: false,
-
-        // use the void* ctor overload
-        base_ctor: Some((
-            cpp_name.clone(),
-            "static_cast<void*>(il2cpp_ptr)".to_string(),
+    let const_to_il2cpp = CppMethodDecl {
+        brief: Some(format!(
+            "Reinterprets this object as the il2cpp struct {il2cpp_t}"
         )),
-        initialized_values: HashMap::new(),
-        brief: None,
-        body: Some(vec![]),
+        is_const: true,
+        ..conversion_method(
+            "to_il2cpp",
+            format!("{il2cpp_t} const*"),
+            vec![],
+            format!("return reinterpret_cast<{il2cpp_t} const*>(this);"),
+        )
     };
 
-    cpp_type
-        .declarations
-        .push(CppMember::MethodDecl(conversion_operator).into());
-    cpp_type
-        .declarations
-        .push(CppMember::MethodDecl(const_conversion_operator).into());
+    let from_il2cpp = CppMethodDecl {
+        brief: Some(format!(
+            "Reinterprets the il2cpp struct {il2cpp_t} as this type"
+        )),
+        instance: false,
+        ..conversion_method(
+            "from_il2cpp",
+            format!("{cpp_name}*"),
+            vec![CppParam {
+                name: "il2cpp_ptr".to_string(),
+                modifiers: "".to_string(),
+                ty: format!("{il2cpp_t}*"),
+                def_value: None,
+            }],
+            format!("return reinterpret_cast<{cpp_name}*>(il2cpp_ptr);"),
+        )
+    };
 
-    cpp_type
-        .declarations
-        .push(CppMember::ConstructorDecl(il2cpp_t_constructor).into());
+    cpp_type.declarations.extend(
+        [to_il2cpp, const_to_il2cpp, from_il2cpp]
+            .into_iter()
+            .map(|m| CppMember::MethodDecl(m).into()),
+    );
 }
 
 fn value_type_convert(cpp_type: &mut CppType, il2cpp_t: &str) {
-    let cpp_name = cpp_type.cpp_name();
-
-    let operator_body = format!("return *static_cast<{il2cpp_t}*>(this->convert());");
-    let conversion_operator = CppMethodDecl {
-        cpp_name: Default::default(),
-        instance: true,
-        return_type: il2cpp_t.to_string(),
-
-        brief: Some(format!("Conversion into il2cpp il2cpp_t {il2cpp_t}")),
-        body: Some(vec![Arc::new(CppLine::make(operator_body))]), // TODO:
-        is_const: false,
-        is_constexpr: true,
-        is_virtual: false,
-        is_implicit_operator: true,
-        is_explicit_operator: false,
+    let cpp_name = cpp_type.cpp_name().clone();
 
-        is_inline: true,
-        is_no_except: true,
-        parameters: vec![],
-        prefix_modifiers: vec![],
-        suffix_modifiers: vec![],
-        template: None,
-    };
-
-    let const_operator_body = format!("return *static_cast<{il2cpp_t} const*>(this->convert());");
-    let const_conversion_operator = CppMethodDecl {
-        cpp_name: Default::default(),
-        instance: true,
-        return_type: il2cpp_t.to_string(),
+    cpp_type
+        .requirements
+        .add_def_include(None, CppInclude::new_system("bit"));
 
-        brief: Some(format!("Conversion into il2cpp il2cpp_t {il2cpp_t}")),
-        body: Some(vec![Arc::new(CppLine::make(const_operator_body))]), // TODO:
+    let conversion_operator = CppMethodDecl {
+        brief: Some(format!("Conversion into il2cpp struct {il2cpp_t}")),
         is_const: true,
         is_constexpr: true,
-        is_virtual: false,
         is_implicit_operator: true,
-        is_explicit_operator: false,
-
-        is_inline: true,
-        is_no_except: true,
-        parameters: vec![],
-        prefix_modifiers: vec![],
-        suffix_modifiers: vec![],
-        template: None,
+        ..conversion_method(
+            "",
+            il2cpp_t.to_string(),
+            vec![],
+            format!("return std::bit_cast<{il2cpp_t}>(*this);"),
+        )
     };
 
-    let il2cpp_t_constructor = CppConstructorDecl {
-        cpp_name: cpp_name.clone(),
-        parameters: vec![CppParam {
-            name: "il2cpp_eq".to_string(),
-            modifiers: "".to_string(),
-            ty: format!("{il2cpp_t} const&").to_string(),
-            def_value: None,
-        }],
-        template: None,
+    let from_il2cpp = CppMethodDecl {
+        brief: Some(format!("Conversion from il2cpp struct {il2cpp_t}")),
+        instance: false,
         is_constexpr: true,
-        is_protected: false,
-        is_explicit: false,
-        is_default: false,
-        is_no_except: true,
-        is_delete: false,
-        // use the array<byte, sz> ctor overload
-        base_ctor: Some((
+        ..conversion_method(
+            "from_il2cpp",
             cpp_name.clone(),
-            format!("std::bit_cast<std::array<std::byte, {VALUE_TYPE_WRAPPER_SIZE}>>(il2cpp_eq)"),
-        )),
-        initialized_values: HashMap::new(),
-        brief: None,
-        body: Some(vec![]),
+            vec![CppParam {
+                name: "il2cpp_value".to_string(),
+                modifiers: "".to_string(),
+                ty: format!("{il2cpp_t} const&"),
+                def_value: None,
+            }],
+            format!("return std::bit_cast<{cpp_name}>(il2cpp_value);"),
+        )
     };
 
-    cpp_type
-        .declarations
-        .push(CppMember::MethodDecl(conversion_operator).into());
-    cpp_type
-        .declarations
-        .push(CppMember::MethodDecl(const_conversion_operator).into());
+    cpp_type.declarations.extend(
+        [conversion_operator, from_il2cpp]
+            .into_iter()
+            .map(|m| CppMember::MethodDecl(m).into()),
+    );
+}
 
-    cpp_type
-        .declarations
-        .push(CppMember::ConstructorDecl(il2cpp_t_constructor).into());
+fn add_layout_asserts(cpp_type: &mut CppType, il2cpp_t: &str) {
+    let cpp_name = cpp_type.cpp_name_components.remove_pointer().combine_all();
+
+    let size_assert = CppStaticAssert {
+        condition: format!("sizeof({cpp_name}) == sizeof({il2cpp_t})"),
+        message: Some(format!("Size mismatch with {il2cpp_t}!")),
+    };
+    let align_assert = CppStaticAssert {
+        condition: format!("alignof({cpp_name}) == alignof({il2cpp_t})"),
+        message: Some(format!("Alignment mismatch with {il2cpp_t}!")),
+    };
+
+    cpp_type.nonmember_declarations.extend(
+        [size_assert, align_assert]
+            .into_iter()
+            .map(|assert| Rc::new(CppNonMember::CppStaticAssert(assert))),
+    );
 }
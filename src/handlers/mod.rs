@@ -1,5 +1,24 @@
+use color_eyre::Result;
+
+use crate::generate::{config::GenerationConfig, metadata::Metadata};
+
+pub mod collections;
 pub mod comment_omit;
 pub mod il2cpp_internals;
+pub mod nullable;
 pub mod object;
+pub mod rules;
+pub mod source_files;
 pub mod unity;
 pub mod value_type;
+
+/// Registers the handlers the CLI uses by default
+pub fn register_default_handlers(metadata: &mut Metadata, config: &GenerationConfig) -> Result<()> {
+    unity::register_unity(metadata, config)?;
+    object::register_system(metadata, config)?;
+    value_type::register_value_type(metadata, config)?;
+    collections::register_collections(metadata)?;
+    nullable::register_nullable(metadata, config)?;
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use std::{fs::File, io::Write};
+
+use brocolib::{global_metadata::TypeDefinitionIndex, runtime_metadata::Il2CppType};
+use color_eyre::Result;
+use log::info;
+
+use crate::{
+    data::name_components::NameComponents,
+    generate::{
+        config::GenerationConfig,
+        context_collection::CppContextCollection,
+        cpp_type::CppType,
+        metadata::{Il2cppFullName, Metadata, TypeUsage},
+    },
+};
+
+/// The wrapper defined in `nullable-utils.hpp`
+const BUNDLED_NULLABLE_WRAPPER: &str = "NullableW";
+
+/// Included by `nullable-utils.hpp` if it exists
+const NULLABLE_RUNTIME_HEADER: &str = "nullable-runtime.hpp";
+
+pub fn register_nullable(metadata: &mut Metadata, config: &GenerationConfig) -> Result<()> {
+    info!("Registering nullable handler!");
+    if let Some(wrapper) = &config.runtime.nullable_wrapper {
+        register_nullable_resolve_handler(metadata, wrapper.clone())?;
+
+        // other wrappers come with their runtime
+        if wrapper == BUNDLED_NULLABLE_WRAPPER {
+            write_nullable_runtime_header(config)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Marks the bundled wrapper as a generic value type and resolves its class through the runtime
+fn write_nullable_runtime_header(config: &GenerationConfig) -> Result<()> {
+    let utils = &config.runtime.utils_namespace;
+    let type_check = config.runtime.type_check_namespace();
+    let wrapper = BUNDLED_NULLABLE_WRAPPER;
+
+    let mut file = File::create(config.dst_internals_path.join(NULLABLE_RUNTIME_HEADER))?;
+
+    writeln!(file, "#pragma once")?;
+    writeln!(file)?;
+    writeln!(
+        file,
+        "#include \"{}\"",
+        config.runtime.includes.typedefs.display()
+    )?;
+    writeln!(file)?;
+    writeln!(file, "MARK_GEN_VAL_T({wrapper});")?;
+    writeln!(file)?;
+    writeln!(file, "template <class T>")?;
+    writeln!(
+        file,
+        "struct {type_check}::il2cpp_no_arg_class<{wrapper}<T>> {{"
+    )?;
+    writeln!(file, "  static inline Il2CppClass* get() {{")?;
+    writeln!(file, "    static auto* klass = {utils}::MakeGeneric(")?;
+    writeln!(
+        file,
+        "        {utils}::GetClassFromName(\"System\", \"Nullable`1\"),"
+    )?;
+    writeln!(
+        file,
+        "        {{{type_check}::il2cpp_no_arg_class<T>::get()}});"
+    )?;
+    writeln!(file, "    return klass;")?;
+    writeln!(file, "  }}")?;
+    writeln!(file, "}};")?;
+    writeln!(file)?;
+    writeln!(file, "template <class T>")?;
+    writeln!(
+        file,
+        "struct {type_check}::il2cpp_no_arg_type<{wrapper}<T>> {{"
+    )?;
+    writeln!(file, "  static inline Il2CppType const* get() {{")?;
+    writeln!(
+        file,
+        "    return &{type_check}::il2cpp_no_arg_class<{wrapper}<T>>::get()->byval_arg;"
+    )?;
+    writeln!(file, "  }}")?;
+    writeln!(file, "}};")?;
+
+    Ok(())
+}
+
+fn register_nullable_resolve_handler(metadata: &mut Metadata, wrapper: String) -> Result<()> {
+    info!("Registering System.Nullable`1 resolve handler!");
+
+    let nullable_tdi = *metadata
+        .name_to_tdi
+        .get(&Il2cppFullName("System", "Nullable`1"))
+        .expect("No System.Nullable`1 TDI found");
+
+    metadata
+        .custom_type_resolve_handler
+        .push(Box::new(move |a, b, c, d, e, f, _| {
+            nullable_resolve_handler(a, b, c, d, e, f, nullable_tdi, &wrapper)
+        }));
+
+    Ok(())
+}
+
+fn nullable_resolve_handler(
+    original: NameComponents,
+    cpp_type: &CppType,
+    _ctx_collection: &CppContextCollection,
+    _metadata: &Metadata,
+    _typ: &Il2CppType,
+    typ_usage: TypeUsage,
+    nullable_tdi: TypeDefinitionIndex,
+    wrapper: &str,
+) -> NameComponents {
+    // generic args are left alone, so generic containers keep the managed type
+    if !matches!(
+        typ_usage,
+        TypeUsage::FieldName
+            | TypeUsage::PropertyName
+            | TypeUsage::Parameter
+            | TypeUsage::ReturnType
+    ) {
+        return original;
+    }
+
+    if cpp_type.self_tag.get_tdi() != nullable_tdi {
+        return original;
+    }
+
+    // the open generic type itself has no value type to wrap
+    let Some(generics) = original.generics.clone() else {
+        return original;
+    };
+
+    NameComponents {
+        namespace: Some("".to_string()),
+        declaring_types: None,
+        name: wrapper.to_string(),
+        generics: Some(generics),
+        is_pointer: false,
+    }
+}
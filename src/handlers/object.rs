@@ -2,20 +2,25 @@ use color_eyre::Result;
 use log::info;
 
 use crate::generate::{
+    config::GenerationConfig,
     cpp_type::CppType,
     cs_type::IL2CPP_OBJECT_TYPE,
     members::CppMember,
     metadata::{Il2cppFullName, Metadata},
+    runtime_profile::RuntimeProfile,
 };
 
-pub fn register_system(metadata: &mut Metadata) -> Result<()> {
+pub fn register_system(metadata: &mut Metadata, config: &GenerationConfig) -> Result<()> {
     info!("Registering system handler!");
-    register_system_object_type_handler(metadata)?;
+    register_system_object_type_handler(metadata, config)?;
 
     Ok(())
 }
 
-fn register_system_object_type_handler(metadata: &mut Metadata) -> Result<()> {
+fn register_system_object_type_handler(
+    metadata: &mut Metadata,
+    config: &GenerationConfig,
+) -> Result<()> {
     info!("Registering System.Object handler!");
 
     let system_object_tdi = metadata
@@ -23,17 +28,19 @@ fn register_system_object_type_handler(metadata: &mut Metadata) -> Result<()> {
         .get(&Il2cppFullName("System", "Object"))
         .expect("No System.Object TDI found");
 
-    metadata
-        .custom_type_handler
-        .insert(*system_object_tdi, Box::new(system_object_handler));
+    let runtime = config.runtime.clone();
+    metadata.custom_type_handler.insert(
+        *system_object_tdi,
+        Box::new(move |cpp_type: &mut CppType| system_object_handler(cpp_type, &runtime)),
+    );
 
     Ok(())
 }
 
-fn system_object_handler(cpp_type: &mut CppType) {
+fn system_object_handler(cpp_type: &mut CppType, runtime: &RuntimeProfile) {
     info!("Found System.Object type, adding systemW!");
     // clear inherit so that bs hook can dof include order shenanigans
-    cpp_type.requirements.need_wrapper();
+    cpp_type.requirements.need_wrapper(runtime);
     cpp_type.inherit = vec![IL2CPP_OBJECT_TYPE.to_string()];
 
     // Remove field because it does not size properly and is not necessary
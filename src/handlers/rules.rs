@@ -0,0 +1,284 @@
+//! Type handlers described by a JSON rules file instead of Rust code.
+//!
+//! ```json
+//! {
+//!     "rules": [
+//!         {
+//!             "match": { "base_type": "UnityEngine.Object" },
+//!             "includes": ["beatsaber-hook/shared/utils/unityw.hpp"],
+//!             "wrap": { "name": "UnityW" }
+//!         },
+//!         {
+//!             "match": { "full_name": "UnityEngine.XR.XRDisplaySubsystem" },
+//!             "opaque": true
+//!         }
+//!     ]
+//! }
+//! ```
+//!
+//! A rule matches either a single type by full name,
+//! or every type assignable to a base type, including the base type itself.
+
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use brocolib::{global_metadata::TypeDefinitionIndex, runtime_metadata::Il2CppType};
+use color_eyre::eyre::{Context, Result};
+use itertools::Itertools;
+use log::{info, warn};
+use serde::Deserialize;
+
+use crate::{
+    data::name_components::NameComponents,
+    generate::{
+        context_collection::CppContextCollection,
+        cpp_type::{CppType, CppTypeRequirements},
+        cs_type::CSType,
+        members::{CppInclude, CppMember},
+        metadata::{Metadata, TypeUsage},
+        type_extensions::TypeDefinitionExtensions,
+    },
+};
+
+/// The pass registering the type and resolve handlers of the rules
+pub const APPLY_RULES_PASS: &str = "apply_rules";
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleMatch {
+    FullName(String),
+    BaseType(String),
+}
+
+/// Wraps the resolved name of a type as `namespace::name<T>`, like `UnityW<T>`
+#[derive(Debug, Clone, Deserialize)]
+pub struct NameWrapper {
+    #[serde(default)]
+    pub namespace: String,
+    pub name: String,
+    #[serde(default = "default_wrap_usages")]
+    pub usages: Vec<TypeUsage>,
+}
+
+fn default_wrap_usages() -> Vec<TypeUsage> {
+    vec![
+        TypeUsage::FieldName,
+        TypeUsage::PropertyName,
+        TypeUsage::GenericArg,
+        TypeUsage::ReturnType,
+    ]
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TypeRule {
+    #[serde(rename = "match")]
+    pub matcher: RuleMatch,
+
+    /// Replaces the base types, also fixing up the base constructor calls
+    #[serde(default)]
+    pub inherit: Option<Vec<String>>,
+    /// Added to the type's definition includes,
+    /// and with `wrap` to those of every type using the wrapped name
+    #[serde(default)]
+    pub includes: Vec<PathBuf>,
+    /// The C++ name of the type, without namespace
+    #[serde(default)]
+    pub rename: Option<String>,
+    #[serde(default)]
+    pub wrap: Option<NameWrapper>,
+    /// C++ names of fields, properties and methods to remove
+    #[serde(default)]
+    pub drop_members: Vec<String>,
+    #[serde(default)]
+    pub opaque: bool,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct TypeRules {
+    pub rules: Vec<TypeRule>,
+}
+
+impl TypeRules {
+    pub fn load(file: &Path) -> Result<Self> {
+        let reader = BufReader::new(File::open(file).with_context(|| format!("{file:?}"))?);
+        let rules: Self =
+            serde_json::from_reader(reader).with_context(|| format!("Parsing rules {file:?}"))?;
+
+        info!("Loaded {} type rules from {file:?}", rules.rules.len());
+        Ok(rules)
+    }
+
+    ///
+    /// Renames have to be registered before types are made,
+    /// so every reference to the type uses the new name
+    ///
+    pub fn register_renames(&self, metadata: &mut Metadata) {
+        for rule in &self.rules {
+            let Some(rename) = &rule.rename else {
+                continue;
+            };
+
+            for tdi in matching_types(metadata, &rule.matcher) {
+                metadata.custom_type_names.insert(tdi, rename.clone());
+            }
+        }
+    }
+
+    /// Registers the remaining actions of each rule as type and resolve handlers
+    pub fn register_handlers(&self, metadata: &mut Metadata) -> Result<()> {
+        for rule in &self.rules {
+            let tdis = matching_types(metadata, &rule.matcher);
+            info!("Rule {:?} matched {} types", rule.matcher, tdis.len());
+
+            if let Some(wrapper) = &rule.wrap {
+                register_wrap_resolve_handler(
+                    metadata,
+                    wrapper.clone(),
+                    rule.includes.clone(),
+                    tdis.iter().copied(),
+                );
+            }
+
+            let rule = Rc::new(rule.clone());
+            for tdi in tdis {
+                let rule = rule.clone();
+                metadata.register_type_handler(
+                    tdi,
+                    Box::new(move |cpp_type: &mut CppType| apply_type_rule(&rule, cpp_type)),
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn matching_types(metadata: &Metadata, matcher: &RuleMatch) -> Vec<TypeDefinitionIndex> {
+    match matcher {
+        RuleMatch::FullName(full_name) => match metadata.find_tdi_by_full_name(full_name) {
+            Some(tdi) => vec![tdi],
+            None => {
+                warn!("No type {full_name} found for rule");
+                vec![]
+            }
+        },
+        RuleMatch::BaseType(base_name) => {
+            let Some(base_tdi) = metadata.find_tdi_by_full_name(base_name) else {
+                warn!("No base type {base_name} found for rule");
+                return vec![];
+            };
+            let type_defs = &metadata.metadata.global_metadata.type_definitions;
+            let base_td = &type_defs[base_tdi];
+
+            type_defs
+                .as_vec()
+                .iter()
+                .enumerate()
+                .filter(|(_, td)| td.is_assignable_to(base_td, metadata.metadata))
+                .map(|(i, _)| TypeDefinitionIndex::new(i as u32))
+                .collect_vec()
+        }
+    }
+}
+
+fn register_wrap_resolve_handler(
+    metadata: &mut Metadata,
+    wrapper: NameWrapper,
+    includes: Vec<PathBuf>,
+    tdis: impl Iterator<Item = TypeDefinitionIndex>,
+) {
+    let tdis: HashSet<TypeDefinitionIndex> = tdis.collect();
+
+    metadata.register_type_resolve_handler(Box::new(
+        move |original: NameComponents,
+              cpp_type: &CppType,
+              _: &CppContextCollection,
+              _: &Metadata,
+              _: &Il2CppType,
+              typ_usage: TypeUsage,
+              requirements: &mut CppTypeRequirements| {
+            if !wrapper.usages.contains(&typ_usage) || !tdis.contains(&cpp_type.self_tag.get_tdi())
+            {
+                return original;
+            }
+
+            // the wrapper is used by whoever names the type, not just the type itself
+            for include in &includes {
+                requirements.add_def_include(None, CppInclude::new_exact(include));
+            }
+
+            NameComponents {
+                namespace: Some(wrapper.namespace.clone()),
+                declaring_types: None,
+                name: wrapper.name.clone(),
+                generics: Some(vec![original.remove_pointer().combine_all()]),
+                is_pointer: false,
+            }
+        },
+    ));
+}
+
+fn apply_type_rule(rule: &TypeRule, cpp_type: &mut CppType) {
+    for include in &rule.includes {
+        cpp_type
+            .requirements
+            .add_def_include(None, CppInclude::new_exact(include));
+    }
+
+    if let Some(inherit) = &rule.inherit {
+        cpp_type.inherit.clone_from(inherit);
+
+        if let Some(base) = inherit.first() {
+            fixup_base_ctors(cpp_type, base);
+        }
+    }
+
+    if !rule.drop_members.is_empty() {
+        let dropped = |m: &Rc<CppMember>| match m.as_ref() {
+            CppMember::FieldDecl(f) => rule.drop_members.contains(&f.cpp_name),
+            CppMember::FieldImpl(f) => rule.drop_members.contains(&f.cpp_name),
+            CppMember::Property(p) => rule.drop_members.contains(&p.cpp_name),
+            CppMember::MethodDecl(m) => rule.drop_members.contains(&m.cpp_name),
+            CppMember::MethodImpl(m) => rule.drop_members.contains(&m.cpp_method_name),
+            _ => false,
+        };
+
+        cpp_type.declarations.retain(|m| !dropped(m));
+        cpp_type.implementations.retain(|m| !dropped(m));
+    }
+
+    // last, so the opaque data is sized against the new base type
+    if rule.opaque {
+        cpp_type.make_opaque_stub("requested by type rule");
+    }
+}
+
+fn fixup_base_ctors(cpp_type: &mut CppType, base: &str) {
+    cpp_type
+        .declarations
+        .iter_mut()
+        .chain(cpp_type.implementations.iter_mut())
+        .filter(|m| {
+            matches!(
+                m.as_ref(),
+                CppMember::ConstructorDecl(_) | CppMember::ConstructorImpl(_)
+            )
+        })
+        .for_each(|m| {
+            let base_ctor = match Rc::make_mut(m) {
+                CppMember::ConstructorDecl(constructor) => &mut constructor.base_ctor,
+                CppMember::ConstructorImpl(constructor) => &mut constructor.base_ctor,
+                _ => return,
+            };
+
+            if let Some(base_ctor) = base_ctor {
+                base_ctor.0 = base.to_string();
+            }
+        });
+}
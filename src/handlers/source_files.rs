@@ -0,0 +1,114 @@
+//! Moves method bodies out of the `_impl.hpp` headers into a `.cpp` source file per type,
+//! so they are compiled once instead of in every translation unit including the type.
+//!
+//! Only non template, non constexpr implementations can move,
+//! their declarations lose `inline` since the definition is no longer in every translation unit.
+
+use std::rc::Rc;
+
+use color_eyre::Result;
+use itertools::Itertools;
+use log::{info, warn};
+
+use crate::generate::{
+    config::GenerationConfig,
+    context_collection::CppContextCollection,
+    cpp_type::CppType,
+    members::{CppMember, CppMethodImpl, CppNonMember, CppParam},
+};
+
+pub const SOURCE_FILES_PASS: &str = "source_files";
+
+pub fn move_bodies_to_sources(
+    context_collection: &mut CppContextCollection,
+    config: &GenerationConfig,
+) -> Result<()> {
+    // definitions of anonymous namespace members can't be in another translation unit
+    if config.use_anonymous_namespace {
+        warn!("Anonymous namespaces are enabled, keeping method bodies in headers");
+        return Ok(());
+    }
+
+    info!("Moving method bodies to source files");
+
+    context_collection
+        .get_mut_cpp_context_collection()
+        .get_mut()
+        .values_mut()
+        .flat_map(|cpp_context| cpp_context.typedef_types.values_mut())
+        .for_each(move_type_bodies);
+
+    Ok(())
+}
+
+fn move_type_bodies(cpp_type: &mut CppType) {
+    cpp_type
+        .nested_types
+        .values_mut()
+        .for_each(move_type_bodies);
+
+    if cpp_type
+        .cpp_template
+        .as_ref()
+        .is_some_and(|t| !t.names.is_empty())
+    {
+        return;
+    }
+
+    let (moved, kept): (Vec<_>, Vec<_>) = cpp_type
+        .implementations
+        .drain(..)
+        .partition(|i| can_move(i));
+
+    cpp_type.implementations = kept;
+
+    for mut implementation in moved {
+        if let CppMember::MethodImpl(method_impl) = Rc::make_mut(&mut implementation) {
+            method_impl.is_inline = false;
+            remove_decl_inline(cpp_type, method_impl);
+        }
+
+        cpp_type.source_implementations.push(implementation);
+    }
+
+    cpp_type.nonmember_implementations.iter_mut().for_each(|n| {
+        if let CppNonMember::SizeStruct(size_struct) = Rc::make_mut(n)
+            && size_struct.template.is_none()
+        {
+            size_struct.out_of_line = true;
+        }
+    });
+}
+
+fn can_move(implementation: &CppMember) -> bool {
+    match implementation {
+        CppMember::MethodImpl(m) => {
+            !m.is_constexpr && m.template.is_none() && m.declaring_type_template.is_none()
+        }
+        CppMember::ConstructorImpl(c) => !c.is_constexpr && c.template.is_none(),
+        _ => false,
+    }
+}
+
+/// The declaration of an out of line method can't be inline either
+fn remove_decl_inline(cpp_type: &mut CppType, method_impl: &CppMethodImpl) {
+    cpp_type.declarations.iter_mut().for_each(|d| {
+        let CppMember::MethodDecl(decl) = d.as_ref() else {
+            return;
+        };
+
+        let matches = decl.body.is_none()
+            && decl.cpp_name == method_impl.cpp_method_name
+            && decl.instance == method_impl.instance
+            && decl.is_const == method_impl.is_const
+            && param_types(&decl.parameters) == param_types(&method_impl.parameters);
+
+        if matches && let CppMember::MethodDecl(decl) = Rc::make_mut(d) {
+            decl.is_inline = false;
+        }
+    });
+}
+
+fn param_types(params: &[CppParam]) -> Vec<&String> {
+    CppParam::params_types(params).collect_vec()
+}
@@ -6,6 +6,7 @@ use std::{path::PathBuf, rc::Rc};
 use crate::{
     data::name_components::NameComponents,
     generate::{
+        config::GenerationConfig,
         context_collection::CppContextCollection,
         cpp_type::CppType,
         members::{CppInclude, CppMember},
@@ -14,15 +15,20 @@ use crate::{
     },
 };
 
-pub fn register_unity(metadata: &mut Metadata) -> Result<()> {
+pub fn register_unity(metadata: &mut Metadata, config: &GenerationConfig) -> Result<()> {
     info!("Registering unity handler!");
     // register_unity_object_type_handler(metadata)?;
-    register_unity_object_type_resolve_handler(metadata)?;
+    if let Some(wrapper) = &config.runtime.unity_object_wrapper {
+        register_unity_object_type_resolve_handler(metadata, wrapper.clone())?;
+    }
 
     Ok(())
 }
 
-fn register_unity_object_type_resolve_handler(metadata: &mut Metadata) -> Result<()> {
+fn register_unity_object_type_resolve_handler(
+    metadata: &mut Metadata,
+    wrapper: String,
+) -> Result<()> {
     info!("Registering UnityEngine.Object resolve handler!");
 
     let unity_object_tdi = *metadata
@@ -32,8 +38,8 @@ fn register_unity_object_type_resolve_handler(metadata: &mut Metadata) -> Result
 
     metadata
         .custom_type_resolve_handler
-        .push(Box::new(move |a, b, c, d, e, f| {
-            unity_object_resolve_handler(a, b, c, d, e, f, unity_object_tdi)
+        .push(Box::new(move |a, b, c, d, e, f, _| {
+            unity_object_resolve_handler(a, b, c, d, e, f, unity_object_tdi, &wrapper)
         }));
 
     Ok(())
@@ -62,6 +68,7 @@ fn unity_object_resolve_handler(
     _typ: &Il2CppType,
     typ_usage: TypeUsage,
     unity_tdi: TypeDefinitionIndex,
+    wrapper: &str,
 ) -> NameComponents {
     if !matches!(
         typ_usage,
@@ -85,7 +92,7 @@ fn unity_object_resolve_handler(
     NameComponents {
         namespace: Some("".to_string()),
         declaring_types: None,
-        name: "UnityW".to_string(),
+        name: wrapper.to_string(),
         generics: Some(vec![original.remove_pointer().combine_all()]),
         is_pointer: false,
     }
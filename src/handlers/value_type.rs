@@ -3,22 +3,25 @@ use std::rc::Rc;
 use color_eyre::Result;
 
 use crate::generate::{
+    config::GenerationConfig,
     cpp_type::CppType,
-    cs_type::{ENUM_WRAPPER_TYPE, VALUE_WRAPPER_TYPE},
     members::CppMember,
     metadata::{Il2cppFullName, Metadata},
 };
 
 use log::info;
 
-pub fn register_value_type(metadata: &mut Metadata) -> Result<()> {
+pub fn register_value_type(metadata: &mut Metadata, config: &GenerationConfig) -> Result<()> {
     info!("Registering value type handler!");
-    register_value_type_object_handler(metadata)?;
+    register_value_type_object_handler(metadata, config)?;
 
     Ok(())
 }
 
-fn register_value_type_object_handler(metadata: &mut Metadata) -> Result<()> {
+fn register_value_type_object_handler(
+    metadata: &mut Metadata,
+    config: &GenerationConfig,
+) -> Result<()> {
     info!("Registering System.ValueType handler!");
     info!("Registering System.Enum handler!");
 
@@ -31,12 +34,17 @@ fn register_value_type_object_handler(metadata: &mut Metadata) -> Result<()> {
         .get(&Il2cppFullName("System", "Enum"))
         .expect("No System.ValueType TDI found");
 
-    metadata
-        .custom_type_handler
-        .insert(*value_type_tdi, Box::new(value_type_handler));
-    metadata
-        .custom_type_handler
-        .insert(*enum_type_tdi, Box::new(enum_type_handler));
+    let value_wrapper_type = config.runtime.value_wrapper_type.clone();
+    let enum_wrapper_type = config.runtime.enum_wrapper_type.clone();
+
+    metadata.custom_type_handler.insert(
+        *value_type_tdi,
+        Box::new(move |cpp_type: &mut CppType| value_type_handler(cpp_type, &value_wrapper_type)),
+    );
+    metadata.custom_type_handler.insert(
+        *enum_type_tdi,
+        Box::new(move |cpp_type: &mut CppType| enum_type_handler(cpp_type, &enum_wrapper_type)),
+    );
 
     Ok(())
 }
@@ -76,12 +84,12 @@ fn unified_type_handler(cpp_type: &mut CppType, _base_ctor: &str) {
     // Remove method size structs
     cpp_type.nonmember_implementations.clear();
 }
-fn value_type_handler(cpp_type: &mut CppType) {
+fn value_type_handler(cpp_type: &mut CppType, value_wrapper_type: &str) {
     info!("Found System.ValueType, removing inheritance!");
     unified_type_handler(
         cpp_type,
         format!(
-            "{VALUE_WRAPPER_TYPE}<0x{:x}>",
+            "{value_wrapper_type}<0x{:x}>",
             cpp_type
                 .size_info
                 .as_ref()
@@ -91,12 +99,12 @@ fn value_type_handler(cpp_type: &mut CppType) {
         .as_str(),
     );
 }
-fn enum_type_handler(cpp_type: &mut CppType) {
+fn enum_type_handler(cpp_type: &mut CppType, enum_wrapper_type: &str) {
     info!("Found System.Enum type, removing inheritance!");
     unified_type_handler(
         cpp_type,
         format!(
-            "{ENUM_WRAPPER_TYPE}<0x{:x}>",
+            "{enum_wrapper_type}<0x{:x}>",
             cpp_type
                 .size_info
                 .as_ref()
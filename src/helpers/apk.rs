@@ -0,0 +1,78 @@
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek},
+    path::Path,
+};
+
+use color_eyre::eyre::{bail, Context, Result};
+use itertools::Itertools;
+use log::info;
+use zip::ZipArchive;
+
+pub const METADATA_ENTRY: &str = "assets/bin/Data/Managed/Metadata/global-metadata.dat";
+
+/// ABIs tried in order when none is given
+const PREFERRED_ABIS: &[&str] = &["arm64-v8a", "armeabi-v7a", "x86_64", "x86"];
+
+pub struct ApkInputs {
+    pub metadata: Vec<u8>,
+    pub libil2cpp: Vec<u8>,
+}
+
+/// Reads global-metadata.dat and libil2cpp.so out of an APK without extracting it.
+/// `abi` picks the `lib/<abi>` directory, otherwise arm64-v8a, armeabi-v7a, x86_64 and x86 are tried in order
+pub fn read_apk_inputs(apk: &Path, abi: Option<&str>) -> Result<ApkInputs> {
+    let file = File::open(apk).with_context(|| format!("{apk:?}"))?;
+    let mut archive =
+        ZipArchive::new(BufReader::new(file)).with_context(|| format!("Reading zip {apk:?}"))?;
+
+    let available_abis = archive
+        .file_names()
+        .filter_map(|name| {
+            name.strip_prefix("lib/")?
+                .strip_suffix("/libil2cpp.so")
+                .map(str::to_string)
+        })
+        .sorted()
+        .collect_vec();
+
+    let abi = match abi {
+        Some(abi) if available_abis.iter().any(|a| a == abi) => abi.to_string(),
+        Some(abi) => bail!(
+            "{apk:?} has no libil2cpp.so for ABI {abi}, available: {}",
+            available_abis.join(", ")
+        ),
+        None => match PREFERRED_ABIS
+            .iter()
+            .find(|preferred| available_abis.iter().any(|a| a == *preferred))
+            .map(|a| a.to_string())
+            .or_else(|| available_abis.first().cloned())
+        {
+            Some(abi) => abi,
+            // split APKs keep native libraries in a separate config APK
+            None => bail!("{apk:?} has no lib/<abi>/libil2cpp.so, is it a split APK?"),
+        },
+    };
+    info!("Reading {apk:?} with ABI {abi}");
+
+    let metadata = read_entry(&mut archive, METADATA_ENTRY)?;
+    let libil2cpp = read_entry(&mut archive, &format!("lib/{abi}/libil2cpp.so"))?;
+
+    Ok(ApkInputs {
+        metadata,
+        libil2cpp,
+    })
+}
+
+fn read_entry<R: Read + Seek>(archive: &mut ZipArchive<R>, name: &str) -> Result<Vec<u8>> {
+    let mut entry = archive
+        .by_name(name)
+        .with_context(|| format!("No {name} in archive"))?;
+
+    let mut data = Vec::with_capacity(entry.size() as usize);
+    entry
+        .read_to_end(&mut data)
+        .with_context(|| format!("Decompressing {name}"))?;
+
+    Ok(data)
+}
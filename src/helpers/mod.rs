@@ -1,2 +1,4 @@
+pub mod apk;
 pub mod cursor;
 pub mod sorting;
+pub mod unwind;
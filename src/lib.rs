@@ -0,0 +1,28 @@
+#![feature(entry_insert)]
+#![feature(let_chains)]
+#![feature(slice_as_chunks)]
+#![feature(read_buf)]
+#![feature(map_try_insert)]
+#![feature(lazy_cell)]
+
+//! Generates C++ headers for il2cpp binaries.
+//!
+//! The pipeline is:
+//! - parse: [`generate::metadata::Metadata::new`] and [`generate::metadata::Metadata::parse`]
+//! - make: [`generate::context_collection::CppContextCollection::make_all`]
+//! - fill: [`generate::context_collection::CppContextCollection::fill_all`]
+//! - write: [`generate::context_collection::CppContextCollection::write_all`]
+//!
+//! Custom [`generate::metadata::TypeHandlerFn`]s and [`generate::metadata::TypeResolveHandlerFn`]s
+//! can be registered on the [`generate::metadata::Metadata`] before filling.
+
+use include_dir::{include_dir, Dir};
+
+pub mod data;
+pub mod generate;
+pub mod handlers;
+pub mod helpers;
+pub mod json;
+
+/// The cordl_internals headers the generated code depends on
+pub static INTERNALS_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/cordl_internals");
@@ -1,50 +1,71 @@
-#![feature(entry_insert)]
-#![feature(let_chains)]
-#![feature(slice_as_chunks)]
-#![feature(read_buf)]
-#![feature(map_try_insert)]
-#![feature(lazy_cell)]
 #![feature(exit_status_error)]
 
-use brocolib::{global_metadata::TypeDefinitionIndex, runtime_metadata::TypeData};
-use color_eyre::{eyre::Context, Result, Section};
-use generate::{config::GenerationConfig, metadata::Metadata};
+use color_eyre::{
+    eyre::{bail, Context},
+    Result, Section,
+};
+use cordl::{
+    generate::{
+        config::{GenerationConfig, MethodLookup},
+        context_collection::CppContextCollection,
+        failures::write_failure_report,
+        fingerprint::InputFingerprint,
+        metadata::Metadata,
+        method_table::{write_method_table, METHOD_TABLE_PASS},
+        passes::{FnPass, PassManager, FILL_TYPES_PASS, REGISTER_HANDLERS_PASS},
+        runtime_profile::{RuntimeProfile, BEATSABER_HOOK_PROFILE},
+        verify::{write_verify_source, VERIFY_PASS},
+    },
+    handlers::{
+        il2cpp_internals::{register_il2cpp_types, IL2CPP_INTERNALS_PASS},
+        rules::{TypeRules, APPLY_RULES_PASS},
+        source_files::{move_bodies_to_sources, SOURCE_FILES_PASS},
+    },
+    helpers::apk::read_apk_inputs,
+    json::json_gen::{make_json, make_json_folder},
+    INTERNALS_DIR,
+};
 use itertools::Itertools;
-extern crate pretty_env_logger;
+
 use filesize::PathExt;
-use include_dir::{include_dir, Dir};
-use json::json_gen::{make_json, make_json_folder};
-use log::{error, info, trace, warn};
+use log::{error, info, warn};
 use rayon::prelude::*;
 use walkdir::DirEntry;
 
-use std::{fs, path::PathBuf, process::Command, sync::LazyLock, time};
+use std::{fs, path::PathBuf, process::Command, time};
 
 use clap::{Parser, Subcommand};
 
-use crate::{
-    generate::{
-        context_collection::CppContextCollection, cpp_type_tag::CppTypeTag,
-        cs_context_collection::CsContextCollection, members::CppMember,
-    },
-    handlers::{comment_omit::remove_coments, object, unity, value_type},
-};
-mod data;
-mod generate;
-mod handlers;
-mod helpers;
-mod json;
-
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
 struct Cli {
     /// The global-metadata.dat file to use
-    #[clap(short, long, value_parser, value_name = "FILE")]
-    metadata: PathBuf,
+    #[clap(
+        short,
+        long,
+        value_parser,
+        value_name = "FILE",
+        required_unless_present = "apk"
+    )]
+    metadata: Option<PathBuf>,
 
     /// The libil2cpp.so file to use
-    #[clap(short, long, value_parser, value_name = "FILE")]
-    libil2cpp: PathBuf,
+    #[clap(
+        short,
+        long,
+        value_parser,
+        value_name = "FILE",
+        required_unless_present = "apk"
+    )]
+    libil2cpp: Option<PathBuf>,
+
+    /// The APK to read global-metadata.dat and libil2cpp.so from, instead of --metadata and --libil2cpp
+    #[clap(long, value_parser, value_name = "FILE", conflicts_with_all = ["metadata", "libil2cpp"])]
+    apk: Option<PathBuf>,
+
+    /// The lib/<ABI> directory of the APK to read libil2cpp.so from, defaults to arm64-v8a if present
+    #[clap(long, value_name = "ABI", requires = "apk")]
+    abi: Option<String>,
 
     /// The path to generated json file
     #[clap(short, long, value_parser, value_name = "FILE")]
@@ -65,6 +86,62 @@ struct Cli {
     #[clap(short, long)]
     gen_generic_methods_specializations: bool,
 
+    /// The path to the report of types blacklisted for inconsistent layouts
+    #[clap(long, value_parser, value_name = "FILE")]
+    layout_report: Option<PathBuf>,
+
+    /// The path to the report of types that failed to generate
+    #[clap(long, value_parser, value_name = "FILE")]
+    failure_report: Option<PathBuf>,
+
+    /// Whether to exit with an error if any type failed to generate
+    #[clap(long)]
+    strict: bool,
+
+    /// The JSON file of declarative type rules to apply
+    #[clap(long, value_parser, value_name = "FILE")]
+    rules: Option<PathBuf>,
+
+    /// Whether to add conversions between types like System.Exception and their libil2cpp structs
+    #[clap(long)]
+    il2cpp_internals: bool,
+
+    /// The runtime library to generate for, a builtin profile name or a JSON profile file
+    #[clap(long, value_name = "PROFILE", default_value = BEATSABER_HOOK_PROFILE)]
+    runtime: String,
+
+    /// How methods are found: name, token or address. address calls non virtual methods directly
+    #[clap(long, value_name = "LOOKUP", default_value = "name")]
+    method_lookup: MethodLookup,
+
+    /// Whether to write hook declarations for every method into a _hooks.hpp header per type
+    #[clap(long)]
+    hook_headers: bool,
+
+    /// Whether to move non template method bodies into a .cpp file per type, written to codegen/src
+    #[clap(long)]
+    source_files: bool,
+
+    /// Whether non generic methods resolve through one shared MethodInfo table instead of a static each
+    #[clap(long)]
+    method_table: bool,
+
+    /// Where to write the method table stats, defaults to codegen/method_table_stats.txt
+    #[clap(long, value_parser, value_name = "FILE")]
+    method_table_report: Option<PathBuf>,
+
+    /// Whether static fields are accessed at their offset in the class static data instead of by name
+    #[clap(long)]
+    static_field_offsets: bool,
+
+    /// Whether to write codegen/src/cordl_verify.cpp, which checks the generated layouts against the running game
+    #[clap(long)]
+    verify_source: bool,
+
+    /// The name of a generation pass to skip, may be repeated
+    #[clap(long, value_name = "PASS")]
+    disable_pass: Vec<String>,
+
     #[clap(subcommand)]
     command: Option<Commands>,
 }
@@ -72,17 +149,27 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {}
 
-pub static STATIC_CONFIG: LazyLock<GenerationConfig> = LazyLock::new(|| GenerationConfig {
-    header_path: PathBuf::from("./codegen/include"),
-    source_path: PathBuf::from("./codegen/src"),
-    dst_internals_path: PathBuf::from("./codegen/include/cordl_internals"),
-    dst_header_internals_file: PathBuf::from(
-        "./codegen/include/cordl_internals/cordl_internals.hpp",
-    ),
-    use_anonymous_namespace: false,
-});
-
-static INTERNALS_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/cordl_internals");
+fn make_config(
+    cli: &Cli,
+    runtime: RuntimeProfile,
+    fingerprint: InputFingerprint,
+) -> GenerationConfig {
+    GenerationConfig {
+        header_path: PathBuf::from("./codegen/include"),
+        source_path: PathBuf::from("./codegen/src"),
+        dst_internals_path: PathBuf::from("./codegen/include/cordl_internals"),
+        dst_header_internals_file: PathBuf::from(
+            "./codegen/include/cordl_internals/cordl_internals.hpp",
+        ),
+        use_anonymous_namespace: false,
+        runtime,
+        method_lookup: cli.method_lookup,
+        hook_headers: cli.hook_headers,
+        method_table: cli.method_table,
+        static_field_offsets: cli.static_field_offsets,
+        fingerprint,
+    }
+}
 
 fn main() -> color_eyre::Result<()> {
     color_eyre::install()?;
@@ -95,44 +182,40 @@ fn main() -> color_eyre::Result<()> {
         info!("Add --format/-f to format with clang-format at end")
     }
 
-    if STATIC_CONFIG.header_path.exists() {
-        std::fs::remove_dir_all(&STATIC_CONFIG.header_path)?;
+    let runtime = RuntimeProfile::from_name_or_file(&cli.runtime)?;
+    info!("Generating for runtime {}", runtime.name);
+
+    let (global_metadata_data, elf_data) = read_inputs(&cli)?;
+    let fingerprint = InputFingerprint::new(&global_metadata_data, &elf_data);
+    info!("Inputs: {}", fingerprint.describe());
+
+    let config = make_config(&cli, runtime, fingerprint);
+
+    if config.header_path.exists() {
+        std::fs::remove_dir_all(&config.header_path)?;
+    }
+    std::fs::create_dir_all(&config.header_path)?;
+    // always cleared, so sources from an earlier run with other flags (e.g. cordl_verify.cpp) don't linger
+    if config.source_path.exists() {
+        std::fs::remove_dir_all(&config.source_path)?;
     }
-    std::fs::create_dir_all(&STATIC_CONFIG.header_path)?;
 
     info!(
         "Copying config to codegen folder {:?}",
-        STATIC_CONFIG.dst_internals_path
+        config.dst_internals_path
     );
 
-    std::fs::create_dir_all(&STATIC_CONFIG.dst_internals_path)?;
+    std::fs::create_dir_all(&config.dst_internals_path)?;
 
     // extract contents of the cordl internals folder into destination
-    INTERNALS_DIR.extract(&STATIC_CONFIG.dst_internals_path)?;
+    INTERNALS_DIR.extract(&config.dst_internals_path)?;
+    config
+        .fingerprint
+        .write_version_header(&config.header_path)?;
 
-    let global_metadata_data = fs::read(cli.metadata).context("il2cpp metadata")?;
-    let elf_data = fs::read(cli.libil2cpp).context("libil2cpp.so shared object")?;
     let il2cpp_metadata = brocolib::Metadata::parse(&global_metadata_data, &elf_data)?;
 
-    let mut metadata = Metadata {
-        metadata: &il2cpp_metadata,
-        code_registration: &il2cpp_metadata.runtime_metadata.code_registration,
-        metadata_registration: &il2cpp_metadata.runtime_metadata.metadata_registration,
-        method_calculations: Default::default(),
-        parent_to_child_map: Default::default(),
-        child_to_parent_map: Default::default(),
-        // TODO: These should come from args to the program?
-        custom_type_handler: Default::default(),
-        custom_type_resolve_handler: Default::default(),
-        name_to_tdi: Default::default(),
-        blacklisted_types: Default::default(),
-        pointer_size: generate::metadata::PointerSize::Bytes8,
-        // For most il2cpp versions
-        packing_field_offset: 7,
-        size_is_default_offset: 12,
-        specified_packing_field_offset: 13,
-        packing_is_default_offset: 11,
-    };
+    let mut metadata = Metadata::new(&il2cpp_metadata);
     let t = time::Instant::now();
     info!("Parsing metadata methods");
     metadata.parse();
@@ -140,12 +223,12 @@ fn main() -> color_eyre::Result<()> {
 
     if let Some(json) = cli.json {
         println!("Writing json file {json:?}");
-        make_json(&metadata, &STATIC_CONFIG, json)?;
+        make_json(&metadata, &config, json)?;
         return Ok(());
     }
     if let Some(json_folder) = cli.multi_json {
         println!("Writing json file {json_folder:?}");
-        make_json_folder(&metadata, &STATIC_CONFIG, json_folder)?;
+        make_json_folder(&metadata, &config, json_folder)?;
         return Ok(());
     }
 
@@ -153,583 +236,137 @@ fn main() -> color_eyre::Result<()> {
 
     // blacklist types
     {
-        let mut blacklist_type = |full_name: &str| {
-            let tdi = metadata
-                .metadata
-                .global_metadata
-                .type_definitions
-                .as_vec()
-                .iter()
-                .enumerate()
-                .find(|(_, t)| t.full_name(metadata.metadata, false) == full_name);
-
-            if let Some((tdi, _td)) = tdi {
-                info!("Blacklisted {full_name}");
-
-                metadata
-                    .blacklisted_types
-                    .insert(TypeDefinitionIndex::new(tdi as u32));
-            } else {
-                warn!("Unable to blacklist {full_name}")
-            }
+        let mut blacklist_type = |full_name: &str| match metadata.blacklist_type(full_name) {
+            true => info!("Blacklisted {full_name}"),
+            false => warn!("Unable to blacklist {full_name}"),
         };
 
         blacklist_type("UnityEngine.XR.XRInputSubsystemDescriptor");
         blacklist_type("UnityEngine.XR.XRMeshSubsystemDescriptor");
         blacklist_type("UnityEngine.XR.XRDisplaySubsystem");
         blacklist_type("UIToolkitUtilities.Controls.Table"); // TODO: Make System.Enum work properly
-                                                             // blacklist_type("NetworkPacketSerializer`2::<>c__DisplayClass4_0`1");
-                                                             // blacklist_type("NetworkPacketSerializer`2::<>c__DisplayClass8_0`1");
-                                                             // blacklist_type("NetworkPacketSerializer`2::<>c__DisplayClass7_0`1");
-                                                             // blacklist_type("NetworkPacketSerializer`2::<>c__DisplayClass5_0`1");
-                                                             // blacklist_type("NetworkPacketSerializer`2::<>c__DisplayClass10_0");
-                                                             // blacklist_type("NetworkPacketSerializer`2::<>c__6`1");
-                                                             // blacklist_type("RpcHandler`1::<>c__DisplayClass14_0`5");
-                                                             // blacklist_type("RpcHandler`1::<>c__DisplayClass10_0`1");
-                                                             // blacklist_type("RpcHandler`1::<>c__DisplayClass11_0`2");
-                                                             // blacklist_type("RpcHandler`1::<>c__DisplayClass12_0`3");
-                                                             // blacklist_type("RpcHandler`1::<>c__DisplayClass13_0`4");
-                                                             // blacklist_type("RpcHandler`1::<>c__DisplayClass14_0`5");
-                                                             // blacklist_type("RpcHandler`1::<>c__DisplayClass15_0`1");
-                                                             // blacklist_type("RpcHandler`1::<>c__DisplayClass16_0`2");
-                                                             // blacklist_type("RpcHandler`1::<>c__DisplayClass17_0`3");
-                                                             // blacklist_type("RpcHandler`1::<>c__DisplayClass18_0`4");
-                                                             // blacklist_type("RpcHandler`1::<>c__DisplayClass19_0`5");
-
-        // Incorrect offsets / sizes due to il2cpp bug
-        blacklist_type("UnityEngine.InputSystem.InputInteractionContext");
-        blacklist_type("UnityEngine.InputSystem.IInputInteraction");
-        blacklist_type("UnityEngine.InputSystem.LowLevel.ActionEvent");
-        blacklist_type("UnityEngine.InputSystem.Interactions.HoldInteraction");
-        blacklist_type("UnityEngine.InputSystem.Interactions.MultiTapInteraction");
-        blacklist_type("UnityEngine.InputSystem.Interactions.PressInteraction");
-        blacklist_type("UnityEngine.InputSystem.Interactions.TapInteraction");
-        blacklist_type("UnityEngine.InputSystem.Interactions.SlowTapInteraction");
-        blacklist_type("UnityEngine.InputSystem.LowLevel.UseWindowsGamingInputCommand");
-        blacklist_type("UnityEngine.InputSystem.LowLevel.EnableIMECompositionCommand");
-        blacklist_type("UnityEngine.InputSystem.LowLevel.MouseState");
-        blacklist_type("UnityEngine.InputSystem.LowLevel.QueryCanRunInBackground");
-        blacklist_type("UnityEngine.InputSystem.LowLevel.QueryEnabledStateCommand");
-        blacklist_type("UnityEngine.InputSystem.Utilities.InputActionTrace");
-        blacklist_type("UnityEngine.InputSystem.Utilities.InputActionTrace::ActionEventPtr");
-        blacklist_type("UnityEngine.InputSystem.Utilities.InputActionTrace::Enumerator");
+
         blacklist_type("System.MonoLimitationAttribute");
     }
-    {
-        let _blacklist_types = |full_name: &str| {
-            let tdis = metadata
-                .metadata
-                .global_metadata
-                .type_definitions
-                .as_vec()
-                .iter()
-                .enumerate()
-                .filter(|(_, t)| t.full_name(metadata.metadata, false).contains(full_name))
-                .collect_vec();
-
-            match tdis.is_empty() {
-                true => warn!("Unable to blacklist {full_name}"),
-                false => {
-                    for (tdi, td) in tdis {
-                        info!("Blacklisted {}", td.full_name(metadata.metadata, true));
-
-                        metadata
-                            .blacklisted_types
-                            .insert(TypeDefinitionIndex::new(tdi as u32));
-                    }
-                }
-            }
-        };
-        // blacklist_types("<>c__DisplayClass");
-    }
-    {
-        // First, make all the contexts
-        info!("Making types");
-        let type_defs = metadata.metadata.global_metadata.type_definitions.as_vec();
-        let total = type_defs.len();
-        for tdi_u64 in 0..total {
-            let tdi = TypeDefinitionIndex::new(tdi_u64 as u32);
-
-            let ty_def = &metadata.metadata.global_metadata.type_definitions[tdi];
-            let _ty = &metadata.metadata_registration.types[ty_def.byval_type_index as usize];
-
-            if ty_def.declaring_type_index != u32::MAX {
-                continue;
-            }
 
-            trace!(
-                "Making types {:.4}% ({tdi_u64}/{total})",
-                (tdi_u64 as f64 / total as f64 * 100.0)
-            );
-            cpp_context_collection.make_from(
-                &metadata,
-                &STATIC_CONFIG,
-                TypeData::TypeDefinitionIndex(tdi),
-                None,
-            );
-            cpp_context_collection.alias_nested_types_il2cpp(
-                tdi,
-                CppTypeTag::TypeDefinitionIndex(tdi),
-                &metadata,
-                false,
-            );
-        }
+    let rules = cli.rules.as_deref().map(TypeRules::load).transpose()?;
+    if let Some(rules) = &rules {
+        rules.register_renames(&mut metadata);
     }
-    {
-        // First, make all the contexts
-        info!("Making nested types");
-        let type_defs = metadata.metadata.global_metadata.type_definitions.as_vec();
-        let total = type_defs.len();
-        for tdi_u64 in 0..total {
-            let tdi = TypeDefinitionIndex::new(tdi_u64 as u32);
-
-            let ty_def = &metadata.metadata.global_metadata.type_definitions[tdi];
 
-            if ty_def.declaring_type_index == u32::MAX {
-                continue;
-            }
-
-            trace!(
-                "Making nested types {:.4}% ({tdi_u64}/{total})",
-                (tdi_u64 as f64 / total as f64 * 100.0)
-            );
-            cpp_context_collection.make_nested_from(&metadata, &STATIC_CONFIG, tdi, None);
-        }
+    let layout_report = cli
+        .layout_report
+        .unwrap_or_else(|| PathBuf::from("./codegen/layout_mismatches.txt"));
+    let mut passes = PassManager::with_default_passes(
+        layout_report,
+        cli.gen_generic_methods_specializations,
+        cli.remove_verbose_comments,
+    );
+    if let Some(rules) = rules {
+        passes.insert_after(
+            REGISTER_HANDLERS_PASS,
+            FnPass::new(APPLY_RULES_PASS, move |_, metadata, _| {
+                rules.register_handlers(metadata)
+            }),
+        )?;
     }
-
-    // {
-    //     let total = metadata.metadata_registration.generic_method_table.len() as f64;
-    //     info!("Making generic type instantiations");
-    //     for (i, generic_class) in metadata
-    //         .metadata_registration
-    //         .generic_method_table
-    //         .iter()
-    //         .enumerate()
-    //     {
-    //         trace!(
-    //             "Making generic type instantiations {:.4}% ({i}/{total})",
-    //             (i as f64 / total * 100.0)
-    //         );
-    //         let method_spec = metadata
-    //             .metadata_registration
-    //             .method_specs
-    //             .get(generic_class.generic_method_index as usize)
-    //             .unwrap();
-
-    //         cpp_context_collection.make_generic_from(method_spec, &mut metadata, &STATIC_CONFIG);
-    //     }
-    // }
-    // {
-    //     let total = metadata.metadata_registration.generic_method_table.len() as f64;
-    //     info!("Filling generic types!");
-    //     for (i, generic_class) in metadata
-    //         .metadata_registration
-    //         .generic_method_table
-    //         .iter()
-    //         .enumerate()
-    //     {
-    //         trace!(
-    //             "Filling generic type instantiations {:.4}% ({i}/{total})",
-    //             (i as f64 / total * 100.0)
-    //         );
-    //         let method_spec = metadata
-    //             .metadata_registration
-    //             .method_specs
-    //             .get(generic_class.generic_method_index as usize)
-    //             .unwrap();
-
-    //         cpp_context_collection.fill_generic_class_inst(
-    //             method_spec,
-    //             &mut metadata,
-    //             &STATIC_CONFIG,
-    //         );
-    //     }
-    // }
-
-    if cli.gen_generic_methods_specializations {
-        let total = metadata.metadata_registration.generic_method_table.len() as f64;
-        info!("Filling generic methods!");
-        for (i, generic_class) in metadata
-            .metadata_registration
-            .generic_method_table
-            .iter()
-            .enumerate()
-        {
-            trace!(
-                "Filling generic method instantiations {:.4}% ({i}/{total})",
-                (i as f64 / total * 100.0)
-            );
-            let method_spec = metadata
-                .metadata_registration
-                .method_specs
-                .get(generic_class.generic_method_index as usize)
-                .unwrap();
-
-            cpp_context_collection.fill_generic_method_inst(
-                method_spec,
-                &mut metadata,
-                &STATIC_CONFIG,
-            );
-        }
+    // registered after the rules pass is inserted, so rules still run last
+    if cli.il2cpp_internals {
+        passes.insert_after(
+            REGISTER_HANDLERS_PASS,
+            FnPass::new(IL2CPP_INTERNALS_PASS, |_, metadata, _| {
+                register_il2cpp_types(metadata)
+            }),
+        )?;
     }
-
-    info!("Registering handlers!");
-    // il2cpp_internals::register_il2cpp_types(&mut metadata)?;
-    unity::register_unity(&mut metadata)?;
-    object::register_system(&mut metadata)?;
-    value_type::register_value_type(&mut metadata)?;
-    info!("Handlers registered!");
-
-    {
-        // Fill them now
-        info!("Filling types");
-        let type_defs = metadata.metadata.global_metadata.type_definitions.as_vec();
-        let total = type_defs.len();
-        for tdi_u64 in 0..total {
-            let tdi = TypeDefinitionIndex::new(tdi_u64 as u32);
-
-            trace!(
-                "Filling type {:.4} ({tdi_u64}/{total})",
-                (tdi_u64 as f64 / total as f64 * 100.0)
-            );
-
-            cpp_context_collection.fill(
-                &metadata,
-                &STATIC_CONFIG,
-                CppTypeTag::TypeDefinitionIndex(tdi),
-            );
-        }
+    if cli.source_files {
+        passes.insert_after(
+            FILL_TYPES_PASS,
+            FnPass::new(SOURCE_FILES_PASS, |collection, _, config| {
+                move_bodies_to_sources(collection, config)
+            }),
+        )?;
+    }
+    if cli.method_table {
+        let report = cli
+            .method_table_report
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("./codegen/method_table_stats.txt"));
+        passes.insert_after(
+            FILL_TYPES_PASS,
+            FnPass::new(METHOD_TABLE_PASS, move |collection, metadata, config| {
+                write_method_table(collection, metadata, config, &report)
+            }),
+        )?;
+    }
+    if cli.verify_source {
+        passes.insert_after(
+            FILL_TYPES_PASS,
+            FnPass::new(VERIFY_PASS, |collection, metadata, config| {
+                write_verify_source(collection, metadata, config)
+            }),
+        )?;
     }
+    for pass in &cli.disable_pass {
+        passes.disable(pass)?;
+    }
+    info!("Running passes: {}", passes.pass_names().join(", "));
+    passes.run(&mut cpp_context_collection, &mut metadata, &config)?;
 
-    if cli.remove_verbose_comments {
-        remove_coments(&mut cpp_context_collection)?;
+    if cli.format {
+        format_files(&config)?;
     }
 
-    const write_all: bool = true;
-    if write_all {
-        cpp_context_collection.write_all(&STATIC_CONFIG)?;
-        cpp_context_collection.write_namespace_headers()?;
-    } else {
-        // for t in &metadata.type_definitions {
-        //     // Handle the generation for a single type
-        //     let dest = open_writer(&metadata, &config, &t);
-        //     write_type(&metadata, &config, &t, &dest);
-        // }
-        fn make_td_tdi(idx: u32) -> TypeData {
-            TypeData::TypeDefinitionIndex(TypeDefinitionIndex::new(idx))
+    let failures = cpp_context_collection.failures();
+    let failure_report = cli
+        .failure_report
+        .unwrap_or_else(|| PathBuf::from("./codegen/failures.json"));
+    // a report from an earlier run would claim failures this run doesn't have
+    if failures.is_empty() && failure_report.exists() {
+        std::fs::remove_file(&failure_report)?;
+    }
+    if !failures.is_empty() {
+        warn!(
+            "{} types failed to generate and were made opaque, writing report to {failure_report:?}",
+            failures.len()
+        );
+        write_failure_report(failures, &failure_report)?;
+
+        if cli.strict {
+            bail!("{} types failed to generate", failures.len());
         }
-        // All indices require updating
-        // cpp_context_collection.get()[&make_td_tdi(123)].write()?;
-        // cpp_context_collection.get()[&make_td_tdi(342)].write()?;
-        // cpp_context_collection.get()[&make_td_tdi(512)].write()?;
-        // cpp_context_collection.get()[&make_td_tdi(1024)].write()?;
-        // cpp_context_collection.get()[&make_td_tdi(600)].write()?;
-        // cpp_context_collection.get()[&make_td_tdi(1000)].write()?;
-        // cpp_context_collection.get()[&make_td_tdi(420)].write()?;
-        // cpp_context_collection.get()[&make_td_tdi(69)].write()?;
-        // cpp_context_collection.get()[&make_td_tdi(531)].write()?;
-        // cpp_context_collection.get()[&make_td_tdi(532)].write()?;
-        // cpp_context_collection.get()[&make_td_tdi(533)].write()?;
-        // cpp_context_collection.get()[&make_td_tdi(534)].write()?;
-        // cpp_context_collection.get()[&make_td_tdi(535)].write()?;
-        // cpp_context_collection.get()[&make_td_tdi(1455)].write()?;
-        info!("Generic type");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| c.get_types().iter().any(|(_, t)| t.cpp_template.is_some()))
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        info!("List Generic type");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| {
-                c.get_types().iter().any(|(_, t)| {
-                    t.cpp_name_components.generics.is_some() && t.cpp_name() == "List_1"
-                })
-            })
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        info!("Value type");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| {
-                c.get_types().iter().any(|(_, t)| {
-                    t.is_value_type && t.name() == "Color" && t.namespace() == "UnityEngine"
-                })
-            })
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        // info!("Nested type");
-        // cpp_context_collection
-        //     .get()
-        //     .iter()
-        //     .find(|(_, c)| {
-        //         c.get_types().iter().any(|(_, t)| {
-        //             t.nested_types
-        //                 .iter()
-        //                 .any(|(_, n)| !n.declarations.is_empty())
-        //         })
-        //     })
-        //     .unwrap()
-        //     .1
-        //     .write()?;
-        // Doesn't exist anymore?
-        // info!("AlignmentUnion type");
-        // cpp_context_collection
-        //     .get()
-        //     .iter()
-        //     .find(|(_, c)| {
-        //         c.get_types()
-        //             .iter()
-        //             .any(|(_, t)| t.is_value_type && &t.name()== "AlignmentUnion")
-        //     })
-        //     .unwrap()
-        //     .1
-        //     .write()?;
-        info!("Array type");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| {
-                c.get_types()
-                    .iter()
-                    .any(|(_, t)| t.name() == "Array" && t.namespace() == "System")
-            })
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        info!("Default param");
-        cpp_context_collection
-            .get()
-            .iter()
-            .filter(|(_, c)| {
-                c.get_types().iter().any(|(_, t)| {
-                    t.implementations.iter().any(|d| {
-                        if let CppMember::MethodImpl(m) = d.as_ref() {
-                            m.parameters.iter().any(|p| p.def_value.is_some())
-                        } else {
-                            false
-                        }
-                    })
-                })
-            })
-            .nth(2)
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        info!("Enum type");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| c.get_types().iter().any(|(_, t)| t.is_enum_type))
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        info!("UnityEngine.Object");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| {
-                c.get_types()
-                    .iter()
-                    .any(|(_, t)| t.name() == "Object" && t.namespace() == "UnityEngine")
-            })
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        info!("BeatmapSaveDataHelpers");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| {
-                c.get_types()
-                    .iter()
-                    .any(|(_, t)| t.name() == "BeatmapSaveDataHelpers")
-            })
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        info!("HMUI.ViewController");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| {
-                c.get_types()
-                    .iter()
-                    .any(|(_, t)| t.namespace() == "HMUI" && t.name() == "ViewController")
-            })
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        info!("UnityEngine.Component");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| {
-                c.get_types()
-                    .iter()
-                    .any(|(_, t)| t.namespace() == "UnityEngine" && t.name() == "Component")
-            })
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        info!("UnityEngine.GameObject");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| {
-                c.get_types()
-                    .iter()
-                    .any(|(_, t)| t.namespace() == "UnityEngine" && t.name() == "GameObject")
-            })
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        info!("MainFlowCoordinator");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| {
-                c.get_types()
-                    .iter()
-                    .any(|(_, t)| t.namespace().is_empty() && t.name() == "MainFlowCoordinator")
-            })
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        info!("OVRPlugin");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| {
-                c.get_types()
-                    .iter()
-                    .any(|(_, t)| t.namespace().is_empty() && t.name() == "OVRPlugin")
-            })
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        info!("HMUI.IValueChanger");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| {
-                c.get_types()
-                    .iter()
-                    .any(|(_, t)| t.namespace() == "HMUI" && t.name() == "IValueChanger`1")
-            })
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        info!("System.ValueType");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| {
-                c.get_types()
-                    .iter()
-                    .any(|(_, t)| t.namespace() == "System" && t.name() == "ValueType")
-            })
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        info!("System.ValueTuple_2");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| {
-                c.get_types()
-                    .iter()
-                    .any(|(_, t)| t.namespace() == "System" && t.name() == "ValueTuple`2")
-            })
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        info!("System.Decimal");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| {
-                c.get_types()
-                    .iter()
-                    .any(|(_, t)| t.namespace() == "System" && t.name() == "Decimal")
-            })
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        info!("System.Enum");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| {
-                c.get_types()
-                    .iter()
-                    .any(|(_, t)| t.namespace() == "System" && t.name() == "Enum")
-            })
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        info!("System.Multicast");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| {
-                c.get_types()
-                    .iter()
-                    .any(|(_, t)| t.namespace() == "System" && t.name() == "MulticastDelegate")
-            })
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        info!("System.Delegate");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| {
-                c.get_types()
-                    .iter()
-                    .any(|(_, t)| t.namespace() == "System" && t.name() == "Delegate")
-            })
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        info!("BeatmapSaveDataVersion3.BeatmapSaveData.EventBoxGroup`1");
-        cpp_context_collection
-            .get()
-            .iter()
-            .find(|(_, c)| {
-                c.get_types()
-                    .iter()
-                    .any(|(_, t)| t.name().contains("EventBoxGroup`1"))
-            })
-            .unwrap()
-            .1
-            .write(&STATIC_CONFIG)?;
-        // for (_, context) in cpp_context_collection.get() {
-        //     context.write().unwrap();
-        // }
     }
 
-    if cli.format {
-        format_files()?;
+    Ok(())
+}
+
+/// The global-metadata.dat and libil2cpp.so contents, from an APK or from the given files
+fn read_inputs(cli: &Cli) -> Result<(Vec<u8>, Vec<u8>)> {
+    if let Some(apk) = &cli.apk {
+        let inputs = read_apk_inputs(apk, cli.abi.as_deref())?;
+        return Ok((inputs.metadata, inputs.libil2cpp));
     }
 
-    Ok(())
+    let (Some(metadata), Some(libil2cpp)) = (&cli.metadata, &cli.libil2cpp) else {
+        bail!("Either --apk or both --metadata and --libil2cpp are required");
+    };
+
+    Ok((
+        fs::read(metadata).context("il2cpp metadata")?,
+        fs::read(libil2cpp).context("libil2cpp.so shared object")?,
+    ))
 }
 
-fn format_files() -> Result<()> {
+fn format_files(config: &GenerationConfig) -> Result<()> {
     info!("Formatting!");
 
     use walkdir::WalkDir;
 
-    let files: Vec<DirEntry> = WalkDir::new(&STATIC_CONFIG.header_path)
+    // generated sources such as cordl_verify.cpp are not under the header path
+    let files: Vec<DirEntry> = [&config.header_path, &config.source_path]
         .into_iter()
+        .unique()
+        .flat_map(WalkDir::new)
         .filter(|f| f.as_ref().is_ok_and(|f| f.path().is_file()))
         .try_collect()?;
 